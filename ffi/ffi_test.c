@@ -0,0 +1,84 @@
+// Smoke test of the C interface, run with `make -C ffi test`
+
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "zkevm_opcode_defs.h"
+
+// `ret.panic r0`
+#define PANIC_ENCODING 0x0431ull
+
+static void test_decode_encode(void) {
+  ZkevmDecodedInstruction instruction;
+  assert(zkevm_decode_instruction(PANIC_ENCODING, &instruction) == ZKEVM_STATUS_OK);
+  assert(instruction.canonical);
+  assert(instruction.src0_operand.kind == ZKEVM_OPERAND_REG_ONLY);
+  assert(instruction.ergs_price > 0);
+
+  uint64_t raw = 0;
+  assert(zkevm_encode_instruction(&instruction, &raw) == ZKEVM_STATUS_OK);
+  assert(raw == PANIC_ENCODING);
+
+  instruction.dst0_reg_idx = 3;
+  assert(zkevm_encode_instruction(&instruction, &raw) == ZKEVM_STATUS_OK);
+  ZkevmDecodedInstruction decoded;
+  assert(zkevm_decode_instruction(raw, &decoded) == ZKEVM_STATUS_OK);
+  assert(decoded.dst0_reg_idx == 3);
+
+  instruction.condition = 200;
+  assert(zkevm_encode_instruction(&instruction, &raw) == ZKEVM_STATUS_INVALID_INSTRUCTION);
+  assert(zkevm_decode_instruction(PANIC_ENCODING, NULL) == ZKEVM_STATUS_NULL_POINTER);
+  assert(zkevm_encode_instruction(NULL, &raw) == ZKEVM_STATUS_NULL_POINTER);
+}
+
+static void test_bytecode_hash(void) {
+  uint8_t bytecode[32] = {0};
+  for (size_t i = 0; i < 4; i++) {
+    bytecode[8 * i + 7] = 0x01;
+  }
+  uint8_t hash[32];
+  assert(zkevm_bytecode_hash(bytecode, sizeof(bytecode), hash) == ZKEVM_STATUS_OK);
+  // version byte, marker byte and the length in words
+  assert(hash[0] == 1 && hash[1] == 0);
+  assert(hash[2] == 0 && hash[3] == 1);
+
+  assert(zkevm_bytecode_hash(bytecode, 16, hash) == ZKEVM_STATUS_INVALID_BYTECODE);
+  assert(zkevm_bytecode_hash(NULL, 32, hash) == ZKEVM_STATUS_NULL_POINTER);
+  assert(zkevm_bytecode_hash(bytecode, sizeof(bytecode), NULL) == ZKEVM_STATUS_NULL_POINTER);
+}
+
+static void test_abi(void) {
+  // fat pointer is packed into the low 128 bits, `offset` goes first
+  uint8_t word[32] = {0};
+  word[31] = 1;
+  word[27] = 2;
+  word[23] = 3;
+  word[19] = 4;
+
+  ZkevmFatPointer pointer;
+  assert(zkevm_parse_fat_pointer(word, &pointer) == ZKEVM_STATUS_OK);
+  assert(pointer.offset == 1 && pointer.memory_page == 2);
+  assert(pointer.start == 3 && pointer.length == 4);
+
+  ZkevmFarCallAbi far_call;
+  assert(zkevm_parse_far_call_abi(word, &far_call) == ZKEVM_STATUS_OK);
+  assert(memcmp(&far_call.memory_quasi_fat_pointer, &pointer, sizeof(pointer)) == 0);
+  assert(far_call.ergs_passed == 0 && !far_call.to_system);
+
+  ZkevmRetAbi ret;
+  assert(zkevm_parse_ret_abi(word, &ret) == ZKEVM_STATUS_OK);
+  assert(ret.memory_quasi_fat_pointer.length == 4);
+
+  assert(zkevm_parse_ret_abi(NULL, &ret) == ZKEVM_STATUS_NULL_POINTER);
+  assert(zkevm_parse_far_call_abi(word, NULL) == ZKEVM_STATUS_NULL_POINTER);
+}
+
+int main(void) {
+  test_decode_encode();
+  test_bytecode_hash();
+  test_abi();
+  printf("ok\n");
+
+  return 0;
+}
@@ -0,0 +1,168 @@
+#ifndef ZKEVM_OPCODE_DEFS_H
+#define ZKEVM_OPCODE_DEFS_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit manually */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define ZKEVM_OPERAND_REG_ONLY 0
+
+#define ZKEVM_OPERAND_REG_OR_IMM 1
+
+#define ZKEVM_OPERAND_FULL 2
+
+typedef enum {
+  ZKEVM_STATUS_OK = 0,
+  ZKEVM_STATUS_NULL_POINTER = 1,
+  /**
+   * Fields of the instruction don't describe any variant of the default ISA version
+   */
+  ZKEVM_STATUS_INVALID_INSTRUCTION = 2,
+  /**
+   * Length of the bytecode is not a multiple of 32, it has an even number of words or it's too long
+   */
+  ZKEVM_STATUS_INVALID_BYTECODE = 3,
+} ZkevmStatus;
+
+typedef struct {
+  /**
+   * One of `ZKEVM_OPERAND_*`
+   */
+  uint8_t kind;
+  /**
+   * `encoding_byte` of `RegOrImmFlags` or `ImmMemHandlerFlags`, 0 for `RegOnly`
+   */
+  uint8_t mode;
+} ZkevmOperand;
+
+typedef struct {
+  /**
+   * `Opcode::variant_idx`
+   */
+  uint8_t opcode;
+  /**
+   * `Opcode::materialize_subvariant_idx`
+   */
+  uint8_t sub_opcode;
+  ZkevmOperand src0_operand;
+  ZkevmOperand dst0_operand;
+  bool flags[2];
+  /**
+   * `Condition::variant_index`
+   */
+  uint8_t condition;
+  uint8_t src0_reg_idx;
+  uint8_t src1_reg_idx;
+  uint8_t dst0_reg_idx;
+  uint8_t dst1_reg_idx;
+  uint16_t imm_0;
+  uint16_t imm_1;
+  /**
+   * Ignored on encoding
+   */
+  uint32_t ergs_price;
+  /**
+   * Ignored on encoding
+   */
+  bool requires_kernel_mode;
+  /**
+   * Ignored on encoding
+   */
+  bool can_be_used_in_static_context;
+  /**
+   * Ignored on encoding. Non-canonical encodings are decoded, but the VM would not execute them
+   */
+  bool canonical;
+} ZkevmDecodedInstruction;
+
+typedef struct {
+  uint32_t offset;
+  uint32_t memory_page;
+  uint32_t start;
+  uint32_t length;
+} ZkevmFatPointer;
+
+typedef struct {
+  ZkevmFatPointer memory_quasi_fat_pointer;
+  uint32_t ergs_passed;
+  uint8_t shard_id;
+  /**
+   * `FarCallForwardPageType` as `u8`
+   */
+  uint8_t forwarding_mode;
+  bool constructor_call;
+  bool to_system;
+} ZkevmFarCallAbi;
+
+typedef struct {
+  ZkevmFatPointer memory_quasi_fat_pointer;
+  /**
+   * `RetForwardPageType` as `u8`
+   */
+  uint8_t page_forwarding_mode;
+} ZkevmRetAbi;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Decodes the instruction from its integer representation, as stored in the bytecode
+ * in big endian order. Any value is decodable, check `canonical` to filter out the ones
+ * that the VM would reject
+ *
+ * # Safety
+ *
+ * `out` must be either null or valid for writes
+ */
+ZkevmStatus zkevm_decode_instruction(uint64_t raw, ZkevmDecodedInstruction *out);
+
+/**
+ * # Safety
+ *
+ * `instruction` must be either null or valid for reads, `out` must be either null or valid for writes
+ */
+ZkevmStatus zkevm_encode_instruction(const ZkevmDecodedInstruction *instruction, uint64_t *out);
+
+/**
+ * Writes the versioned code hash of the bytecode into `out`
+ *
+ * # Safety
+ *
+ * `bytecode` must be valid for reads of `length` bytes (or may be null if `length` is 0),
+ * `out` must be either null or valid for writes of 32 bytes
+ */
+ZkevmStatus zkevm_bytecode_hash(const uint8_t *bytecode, size_t length, uint8_t *out);
+
+/**
+ * ABI words are passed as 32 bytes in big endian order
+ *
+ * # Safety
+ *
+ * `word` must be either null or valid for reads of 32 bytes, `out` must be either null or valid for writes
+ */
+ZkevmStatus zkevm_parse_fat_pointer(const uint8_t *word, ZkevmFatPointer *out);
+
+/**
+ * # Safety
+ *
+ * `word` must be either null or valid for reads of 32 bytes, `out` must be either null or valid for writes
+ */
+ZkevmStatus zkevm_parse_far_call_abi(const uint8_t *word, ZkevmFarCallAbi *out);
+
+/**
+ * # Safety
+ *
+ * `word` must be either null or valid for reads of 32 bytes, `out` must be either null or valid for writes
+ */
+ZkevmStatus zkevm_parse_ret_abi(const uint8_t *word, ZkevmRetAbi *out);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* ZKEVM_OPCODE_DEFS_H */
@@ -0,0 +1,96 @@
+# Build the module into the current virtualenv with `maturin develop` and run
+# `python -m unittest discover python/tests` from the repository root
+
+import unittest
+
+import zkevm_opcode_defs as zk
+
+# `ret.panic r0`
+PANIC_ENCODING = 0x0431
+NOP_ENCODING = 0x0001
+
+
+class DecodedOpcodeTest(unittest.TestCase):
+    def test_decode_encode(self):
+        decoded = zk.DecodedOpcode.decode(PANIC_ENCODING)
+        self.assertEqual(decoded.variant.opcode, "Ret.Panic")
+        self.assertEqual(decoded.condition, "Always")
+        self.assertEqual(decoded.encode(), PANIC_ENCODING)
+        self.assertEqual(decoded.to_bytes(), PANIC_ENCODING.to_bytes(8, "big"))
+        self.assertIn("Ret(Panic)", str(decoded))
+
+        decoded = zk.DecodedOpcode.from_bytes(PANIC_ENCODING.to_bytes(8, "big"))
+        decoded.dst0_reg_idx = 3
+        decoded.imm_1 = 0xFFFF
+        self.assertEqual(zk.DecodedOpcode.decode(decoded.encode()).dst0_reg_idx, 3)
+
+        with self.assertRaises(ValueError):
+            zk.DecodedOpcode.from_bytes(b"\x00" * 7)
+        with self.assertRaises(ValueError):
+            decoded.condition = "Never"
+        decoded.src0_reg_idx = 16
+        with self.assertRaises(ValueError):
+            decoded.encode()
+
+    def test_construct(self):
+        variant = zk.DecodedOpcode.decode(NOP_ENCODING).variant
+        decoded = zk.DecodedOpcode(variant, condition="Eq", imm_0=5)
+        self.assertEqual(zk.DecodedOpcode.decode(decoded.encode()).imm_0, 5)
+        self.assertEqual(zk.DecodedOpcode.decode(decoded.encode()).condition, "Eq")
+
+    def test_canonical(self):
+        self.assertTrue(zk.is_canonical_encoding(PANIC_ENCODING))
+        self.assertFalse(zk.is_canonical_encoding(PANIC_ENCODING | (1 << 11)))
+
+    def test_variant_properties(self):
+        table = zk.OpcodeVariant.table()
+        panic = zk.DecodedOpcode.decode(PANIC_ENCODING).variant
+        self.assertIn(panic, table)
+        self.assertTrue(panic.can_be_used_in_static_context)
+        self.assertFalse(panic.requires_kernel_mode)
+        self.assertGreater(panic.ergs_price, 0)
+        self.assertEqual(len(panic.flags), 2)
+        self.assertTrue(any(el.requires_kernel_mode for el in table))
+
+
+class AbiTest(unittest.TestCase):
+    def test_fat_pointer(self):
+        pointer = zk.FatPointer(offset=1, memory_page=2, start=3, length=4)
+        value = pointer.to_int()
+        self.assertEqual(value, 1 | (2 << 32) | (3 << 64) | (4 << 96))
+        self.assertEqual(zk.FatPointer.from_int(value), pointer)
+        with self.assertRaises(OverflowError):
+            zk.FatPointer.from_int(1 << 256)
+        with self.assertRaises(OverflowError):
+            zk.FatPointer.from_int(-1)
+
+    def test_far_call_and_ret(self):
+        value = (1 << 128) | zk.FatPointer(length=32).to_int()
+        abi = zk.FarCallABI.from_int(value)
+        self.assertEqual(abi.memory_quasi_fat_pointer.length, 32)
+        self.assertEqual(abi.forwarding_mode, "UseHeap")
+        self.assertFalse(abi.to_system)
+
+        ret = zk.RetABI.from_int(value)
+        self.assertEqual(ret.memory_quasi_fat_pointer.length, 32)
+        self.assertEqual(ret.page_forwarding_mode, "UseHeap")
+
+
+class BytecodeTest(unittest.TestCase):
+    def test_decode_and_hash(self):
+        word = NOP_ENCODING.to_bytes(8, "big") * 3 + PANIC_ENCODING.to_bytes(8, "big")
+        instructions = zk.decode_bytecode(word)
+        self.assertEqual([el.encode() for el in instructions][-1], PANIC_ENCODING)
+
+        code_hash = zk.bytecode_to_code_hash(word)
+        self.assertEqual(len(code_hash), 32)
+        self.assertEqual(code_hash[:4], bytes([1, 0, 0, 1]))
+
+        with self.assertRaises(ValueError):
+            zk.bytecode_to_code_hash(word * 2)
+        with self.assertRaises(ValueError):
+            zk.decode_bytecode(word[:16])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,393 @@
+use alloc::{vec, vec::Vec};
+
+use ethereum_types::U256;
+
+use crate::decoding::{AllowedPcOrImm, EncodingModeProduction, VmEncodingMode};
+use crate::definitions::compute_encoding_density;
+use crate::utils::{check_bytecode_length_for_mode, BytecodeHashError};
+use crate::{ISAVersion, ImmMemHandlerFlags, Opcode, Operand, RET_TO_LABEL_BIT_IDX};
+
+/// Empty bytecode can not be executed nor hashed
+pub const MIN_BYTECODE_LENGTH_IN_WORDS: usize = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BytecodeValidationError {
+    TooShort {
+        num_words: usize,
+        min_num_words: usize,
+    },
+    /// Bytecode can not be hashed
+    Hash(BytecodeHashError),
+    NonCanonicalEncoding {
+        pc: u64,
+    },
+    UnknownVariant {
+        pc: u64,
+        variant_index: usize,
+        version: ISAVersion,
+    },
+    JumpOutOfBounds {
+        pc: u64,
+        target: u64,
+        code_length_in_instructions: u64,
+    },
+    CodePageReadOutOfBounds {
+        pc: u64,
+        word_index: u64,
+        code_length_in_words: u64,
+    },
+}
+
+impl core::fmt::Display for BytecodeValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BytecodeValidationError::TooShort {
+                num_words,
+                min_num_words,
+            } => write!(
+                f,
+                "bytecode has {} words, while at least {} are required",
+                num_words, min_num_words
+            ),
+            BytecodeValidationError::Hash(err) => write!(f, "{}", err),
+            BytecodeValidationError::NonCanonicalEncoding { pc } => {
+                write!(f, "instruction at pc {} has non-zero unused bits", pc)
+            }
+            BytecodeValidationError::UnknownVariant {
+                pc,
+                variant_index,
+                version,
+            } => write!(
+                f,
+                "instruction at pc {} has variant index {} that is unknown in {:?}",
+                pc, variant_index, version
+            ),
+            BytecodeValidationError::JumpOutOfBounds {
+                pc,
+                target,
+                code_length_in_instructions,
+            } => write!(
+                f,
+                "instruction at pc {} targets pc {} beyond the code length of {} instructions",
+                pc, target, code_length_in_instructions
+            ),
+            BytecodeValidationError::CodePageReadOutOfBounds {
+                pc,
+                word_index,
+                code_length_in_words,
+            } => write!(
+                f,
+                "instruction at pc {} reads word {} beyond the code length of {} words",
+                pc, word_index, code_length_in_words
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BytecodeValidationError {}
+
+/// All the problems found in the bytecode, in the order of their position
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BytecodeValidationReport {
+    pub errors: Vec<BytecodeValidationError>,
+}
+
+impl core::fmt::Display for BytecodeValidationReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "bytecode has {} problem(s)", self.errors.len())?;
+        for err in self.errors.iter() {
+            write!(f, "\n{}", err)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BytecodeValidationReport {}
+
+/// Checks the bytecode beyond what is needed to hash it. Instructions are decoded
+/// from the beginning of the code until `data_section_start` (in words). If it's not
+/// known, the data section is assumed to start at the lowest word that is read from
+/// the code page by the instructions before it
+pub fn validate_bytecode(
+    bytecode_words: &[[u8; 32]],
+    version: ISAVersion,
+    data_section_start: Option<usize>,
+) -> Result<(), BytecodeValidationReport> {
+    validate_bytecode_for_mode::<8, EncodingModeProduction>(
+        bytecode_words,
+        version,
+        data_section_start,
+    )
+}
+
+pub fn validate_bytecode_for_mode<const N: usize, E: VmEncodingMode<N>>(
+    bytecode_words: &[[u8; 32]],
+    version: ISAVersion,
+    data_section_start: Option<usize>,
+) -> Result<(), BytecodeValidationReport> {
+    let mut errors = vec![];
+
+    let num_words = bytecode_words.len();
+    if num_words < MIN_BYTECODE_LENGTH_IN_WORDS {
+        errors.push(BytecodeValidationError::TooShort {
+            num_words,
+            min_num_words: MIN_BYTECODE_LENGTH_IN_WORDS,
+        });
+
+        return Err(BytecodeValidationReport { errors });
+    }
+
+    if let Err(err) = check_bytecode_length_for_mode::<N, E>(num_words) {
+        errors.push(BytecodeValidationError::Hash(err));
+    }
+
+    let instructions_per_word = 32 / N;
+    let code_length_in_words = num_words as u64;
+    let code_length_in_instructions = (num_words * instructions_per_word) as u64;
+    // table is filled with invalid opcodes beyond this index
+    let num_known_variants = compute_encoding_density(version);
+
+    let infer_data_section = data_section_start.is_none();
+    let mut data_section_start = data_section_start.map_or(num_words, |el| el.min(num_words));
+
+    for (word_idx, word) in bytecode_words.iter().enumerate() {
+        if word_idx >= data_section_start {
+            break;
+        }
+
+        let word = U256::from_big_endian(word);
+        for slot in 0..instructions_per_word {
+            let pc = (word_idx * instructions_per_word + slot) as u64;
+            let raw = E::integer_representaiton_from_u256(
+                word,
+                E::PcOrImm::from_u64_clipped(slot as u64),
+            );
+
+            if !E::is_canonical_encoding(raw) {
+                errors.push(BytecodeValidationError::NonCanonicalEncoding { pc });
+            }
+
+            let (decoded, variant_index) = E::parse_preliminary_variant_and_absolute_number(raw);
+            let variant_index = variant_index.into_usize();
+            if variant_index >= num_known_variants {
+                errors.push(BytecodeValidationError::UnknownVariant {
+                    pc,
+                    variant_index,
+                    version,
+                });
+                continue;
+            }
+
+            let variant = decoded.variant;
+            let imm_0 = decoded.imm_0.as_u64();
+            let imm_1 = decoded.imm_1.as_u64();
+
+            let targets = match variant.opcode {
+                Opcode::Jump(_)
+                    if variant.src0_operand_type
+                        == Operand::Full(ImmMemHandlerFlags::UseImm16Only) =>
+                {
+                    [Some(imm_0), None]
+                }
+                // callee and exception handler
+                Opcode::NearCall(_) => [Some(imm_0), Some(imm_1)],
+                // exception handler
+                Opcode::FarCall(_) => [Some(imm_0), None],
+                Opcode::Ret(_) if variant.flags[RET_TO_LABEL_BIT_IDX] => [Some(imm_0), None],
+                _ => [None, None],
+            };
+
+            for target in targets.into_iter().flatten() {
+                if target >= code_length_in_instructions {
+                    errors.push(BytecodeValidationError::JumpOutOfBounds {
+                        pc,
+                        target,
+                        code_length_in_instructions,
+                    });
+                }
+            }
+
+            if variant.src0_operand_type != Operand::Full(ImmMemHandlerFlags::UseCodePage) {
+                continue;
+            }
+
+            // address is `reg + imm_0`, and we can only check it if the register is r0,
+            // that is always zero. Otherwise `imm_0` is the usual base of a constants table
+            if decoded.src0_reg_idx == 0 && imm_0 >= code_length_in_words {
+                errors.push(BytecodeValidationError::CodePageReadOutOfBounds {
+                    pc,
+                    word_index: imm_0,
+                    code_length_in_words,
+                });
+            } else if infer_data_section && imm_0 as usize > word_idx {
+                data_section_start = core::cmp::min(data_section_start, imm_0 as usize);
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(BytecodeValidationReport { errors })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DecodedOpcode, OpcodeVariant, OPCODES_TABLE};
+
+    fn find_variant(predicate: impl Fn(&OpcodeVariant) -> bool) -> OpcodeVariant {
+        *OPCODES_TABLE.iter().find(|el| predicate(el)).unwrap()
+    }
+
+    fn encode(variant: OpcodeVariant, src0_reg_idx: u8, imm_0: u16, imm_1: u16) -> u64 {
+        DecodedOpcode::<8, EncodingModeProduction> {
+            variant,
+            src0_reg_idx,
+            imm_0,
+            imm_1,
+            ..Default::default()
+        }
+        .serialize_as_integer()
+    }
+
+    fn word(instructions: [u64; 4]) -> [u8; 32] {
+        let mut result = [0u8; 32];
+        for (dst, el) in result.chunks_exact_mut(8).zip(instructions.iter()) {
+            dst.copy_from_slice(&el.to_be_bytes());
+        }
+
+        result
+    }
+
+    fn nop() -> u64 {
+        EncodingModeProduction::nop_encoding()
+    }
+
+    fn panic() -> u64 {
+        EncodingModeProduction::exception_revert_encoding()
+    }
+
+    fn jump(target: u16) -> u64 {
+        let variant = find_variant(|el| {
+            matches!(el.opcode, Opcode::Jump(_))
+                && el.src0_operand_type == Operand::Full(ImmMemHandlerFlags::UseImm16Only)
+        });
+
+        encode(variant, 0, target, 0)
+    }
+
+    fn code_page_read(reg: u8, offset: u16) -> u64 {
+        let variant = find_variant(|el| {
+            matches!(el.opcode, Opcode::Add(_))
+                && el.src0_operand_type == Operand::Full(ImmMemHandlerFlags::UseCodePage)
+        });
+
+        encode(variant, reg, offset, 0)
+    }
+
+    fn errors(
+        bytecode: &[[u8; 32]],
+        data_section_start: Option<usize>,
+    ) -> Vec<BytecodeValidationError> {
+        validate_bytecode(bytecode, ISAVersion(1), data_section_start)
+            .err()
+            .map(|el| el.errors)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn valid_bytecode() {
+        let bytecode = [word([jump(3), nop(), nop(), panic()])];
+        assert_eq!(validate_bytecode(&bytecode, ISAVersion(1), None), Ok(()));
+    }
+
+    #[test]
+    fn length_errors() {
+        assert_eq!(
+            errors(&[], None),
+            vec![BytecodeValidationError::TooShort {
+                num_words: 0,
+                min_num_words: MIN_BYTECODE_LENGTH_IN_WORDS
+            }]
+        );
+
+        let nops = word([nop(); 4]);
+        assert_eq!(
+            errors(&[nops, nops], None),
+            vec![BytecodeValidationError::Hash(
+                BytecodeHashError::EvenNumberOfWords { num_words: 2 }
+            )]
+        );
+
+        let num_words = u16::MAX as usize + 2;
+        assert_eq!(
+            errors(&vec![nops; num_words], None),
+            vec![BytecodeValidationError::Hash(BytecodeHashError::TooLong {
+                num_words,
+                max_num_words: u16::MAX as usize
+            })]
+        );
+    }
+
+    #[test]
+    fn instruction_errors() {
+        let unused_bit = 1u64 << 11;
+        let unknown_variant = (1u64 << 11) - 1;
+        let bytecode = [word([
+            nop() | unused_bit,
+            unknown_variant,
+            jump(4),
+            code_page_read(0, 1),
+        ])];
+
+        assert_eq!(
+            errors(&bytecode, None),
+            vec![
+                BytecodeValidationError::NonCanonicalEncoding { pc: 0 },
+                BytecodeValidationError::UnknownVariant {
+                    pc: 1,
+                    variant_index: unknown_variant as usize,
+                    version: ISAVersion(1)
+                },
+                BytecodeValidationError::JumpOutOfBounds {
+                    pc: 2,
+                    target: 4,
+                    code_length_in_instructions: 4
+                },
+                BytecodeValidationError::CodePageReadOutOfBounds {
+                    pc: 3,
+                    word_index: 1,
+                    code_length_in_words: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn trailing_constants() {
+        let constants = [0xffu8; 32];
+        for reg in [0, 1] {
+            let bytecode = [
+                word([
+                    code_page_read(reg, 1),
+                    code_page_read(reg, 2),
+                    nop(),
+                    panic(),
+                ]),
+                constants,
+                constants,
+            ];
+            assert_eq!(errors(&bytecode, None), vec![], "read through r{}", reg);
+        }
+
+        // constants that are never read can only be skipped if the caller knows where they are
+        let bytecode = [word([nop(), nop(), nop(), panic()]), constants, constants];
+        assert_eq!(errors(&bytecode, None).len(), 16);
+        assert_eq!(errors(&bytecode, Some(1)), vec![]);
+    }
+}
@@ -1,35 +1,79 @@
-use std::fs::File;
-use std::io::Write;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 
 use zkevm_opcode_defs::system_params::{
     ERGS_PER_CIRCUIT, INITIAL_STORAGE_WRITE_PUBDATA_BYTES, MAX_PUBDATA_PER_BLOCK,
     MAX_TX_ERGS_LIMIT, REPEATED_STORAGE_WRITE_PUBDATA_BYTES,
 };
-
-/// The number of input "units" the corresponding circuits could take.
-/// It is assumed that the actual capacity of the circuits
-/// below is greater or equal to the values provided there.
-/// Some margin is suggested to not conduct a reprice upon every minor prover change.
-pub const CYCLES_PER_VM_SNAPSHOT: u32 = 23000;
-pub const CYCLES_PER_RAM_PERMUTATION: u32 = 260000;
-pub const CYCLES_PER_CODE_DECOMMITTER: u32 = 12100;
-pub const CYCLES_PER_STORAGE_APPLICATION: u32 = 118;
-pub const CYCLES_PER_KECCAK256_CIRCUIT: u32 = 2050;
-pub const CYCLES_PER_SHA256_CIRCUIT: u32 = 11500;
-pub const CYCLES_PER_ECRECOVER_CIRCUIT: u32 = 72;
-pub const CYCLES_FOR_CODE_DECOMMITTER_SORTER: u32 = 192500;
-pub const CYCLES_FOR_LOG_DEMUXER: u32 = 101500;
-pub const CYCLES_FOR_STORAGE_SORTER: u32 = 79000;
-pub const CYCLES_FOR_EVENTS_OR_L1_MESSAGES_SORTER: u32 = 88000;
-
-/// This kinds of circuit will always remain single-instance
-pub const LIMIT_FOR_L1_MESSAGES_MERKLIZER: u32 = 512;
-pub const LIMIT_FOR_INITIAL_WRITES_PUBDATA_HASHER: u32 = 4600;
-pub const LIMIT_FOR_REPEATED_WRITES_PUBDATA_HASHER: u32 = 7400;
+use zkevm_opcode_defs::*;
 
 /// Returns ceil(a/b)
 const fn ceil_div(a: u32, b: u32) -> u32 {
-    (a + b - 1) / b
+    a.div_ceil(b)
+}
+
+/// Capacities are only limited to be positive, so the products of them may not fit
+fn checked_capacity_product(name: &str, capacity: u32, multiplier: u32) -> Result<u32, String> {
+    capacity.checked_mul(multiplier).ok_or_else(|| {
+        format!(
+            "capacity `{}` = {} is too large, {} times it does not fit into u32",
+            name, capacity, multiplier
+        )
+    })
+}
+
+fn set_capacity(capacities: &mut CircuitCapacities, name: &str, value: u32) -> Result<(), String> {
+    if value == 0 {
+        return Err(format!("capacity `{}` must be positive", name));
+    }
+
+    let field = match name.to_ascii_uppercase().as_str() {
+        "CYCLES_PER_VM_SNAPSHOT" => &mut capacities.cycles_per_vm_snapshot,
+        "CYCLES_PER_RAM_PERMUTATION" => &mut capacities.cycles_per_ram_permutation,
+        "CYCLES_PER_CODE_DECOMMITTER" => &mut capacities.cycles_per_code_decommitter,
+        "CYCLES_PER_STORAGE_APPLICATION" => &mut capacities.cycles_per_storage_application,
+        "CYCLES_PER_KECCAK256_CIRCUIT" => &mut capacities.cycles_per_keccak256_circuit,
+        "CYCLES_PER_SHA256_CIRCUIT" => &mut capacities.cycles_per_sha256_circuit,
+        "CYCLES_PER_ECRECOVER_CIRCUIT" => &mut capacities.cycles_per_ecrecover_circuit,
+        "CYCLES_FOR_CODE_DECOMMITTER_SORTER" => &mut capacities.cycles_for_code_decommitter_sorter,
+        "CYCLES_FOR_LOG_DEMUXER" => &mut capacities.cycles_for_log_demuxer,
+        "CYCLES_FOR_STORAGE_SORTER" => &mut capacities.cycles_for_storage_sorter,
+        "CYCLES_FOR_EVENTS_OR_L1_MESSAGES_SORTER" => {
+            &mut capacities.cycles_for_events_or_l1_messages_sorter
+        }
+        "LIMIT_FOR_L1_MESSAGES_MERKLIZER" => &mut capacities.limit_for_l1_messages_merklizer,
+        "LIMIT_FOR_INITIAL_WRITES_PUBDATA_HASHER" => {
+            &mut capacities.limit_for_initial_writes_pubdata_hasher
+        }
+        "LIMIT_FOR_REPEATED_WRITES_PUBDATA_HASHER" => {
+            &mut capacities.limit_for_repeated_writes_pubdata_hasher
+        }
+        _ => return Err(format!("unknown circuit capacity `{}`", name)),
+    };
+    *field = value;
+
+    Ok(())
+}
+
+/// Accepts either a flat TOML file with `NAME = value` entries or a flat JSON object.
+/// Names are the ones of the `CYCLES_*` and `LIMIT_*` constants, capacities that are not listed keep their defaults
+fn capacities_from_file_contents(
+    contents: &str,
+    is_json: bool,
+) -> Result<CircuitCapacities, String> {
+    // tables, arrays and anything that is not an `u32` are rejected by the parsers
+    let entries: BTreeMap<String, u32> = if is_json {
+        serde_json::from_str(contents).map_err(|err| err.to_string())?
+    } else {
+        toml::from_str(contents).map_err(|err| err.to_string())?
+    };
+
+    let mut capacities = CircuitCapacities::default();
+    for (name, value) in entries.into_iter() {
+        set_capacity(&mut capacities, &name, value)?;
+    }
+
+    Ok(capacities)
 }
 
 fn ergs_constant(name: &str, value: u32) -> String {
@@ -40,92 +84,97 @@ fn comment(comment: &str) -> String {
     format!("\n// {}\n", comment)
 }
 
-fn save_circiut_prices(prices: String, filepath: &str) {
-    let file_content = vec![
-        "// This file is auto-generated, do not edit it manually\n".to_owned(),
-        "// Any changes to this file require system upgrade!\n\n".to_owned(),
-        prices,
-    ]
-    .concat();
-    let mut f = File::create(filepath).expect("Unable to create file");
-    f.write_all(file_content.as_bytes())
-        .expect("Unable to write data");
-}
-
-fn main() {
-    let max_possible_initial_bytes_circuit_wise =
-        LIMIT_FOR_INITIAL_WRITES_PUBDATA_HASHER * (INITIAL_STORAGE_WRITE_PUBDATA_BYTES as u32);
+fn generate_circiut_prices(capacities: &CircuitCapacities) -> Result<String, String> {
+    let max_possible_initial_bytes_circuit_wise = checked_capacity_product(
+        "LIMIT_FOR_INITIAL_WRITES_PUBDATA_HASHER",
+        capacities.limit_for_initial_writes_pubdata_hasher,
+        INITIAL_STORAGE_WRITE_PUBDATA_BYTES as u32,
+    )?;
     let min_price_for_initial_pubdata_write =
         if MAX_PUBDATA_PER_BLOCK <= max_possible_initial_bytes_circuit_wise {
             // The cost of pubdata alone would not let the users to run out of this circuit
             // before the end of the transaction. The users can safely pay nothing for the DDoS security.
             0
         } else {
-            ceil_div(MAX_TX_ERGS_LIMIT, LIMIT_FOR_INITIAL_WRITES_PUBDATA_HASHER)
+            ceil_div(
+                MAX_TX_ERGS_LIMIT,
+                capacities.limit_for_initial_writes_pubdata_hasher,
+            )
         };
 
-    let max_possible_repeated_bytes_circuit_wise =
-        LIMIT_FOR_REPEATED_WRITES_PUBDATA_HASHER * (REPEATED_STORAGE_WRITE_PUBDATA_BYTES as u32);
+    let max_possible_repeated_bytes_circuit_wise = checked_capacity_product(
+        "LIMIT_FOR_REPEATED_WRITES_PUBDATA_HASHER",
+        capacities.limit_for_repeated_writes_pubdata_hasher,
+        REPEATED_STORAGE_WRITE_PUBDATA_BYTES as u32,
+    )?;
+    // Each round of decommitter outputs 64 bytes of the code,
+    // while the user will pay for each word.
+    let code_decommitter_words_per_circuit = checked_capacity_product(
+        "CYCLES_PER_CODE_DECOMMITTER",
+        capacities.cycles_per_code_decommitter,
+        2,
+    )?;
     let min_price_for_repeated_pubdata_write =
         if MAX_PUBDATA_PER_BLOCK <= max_possible_repeated_bytes_circuit_wise {
             // The cost of pubdata alone would not let the users to run out of this circuit
             // before the end of the transaction. The users can safely pay nothing for the DDoS security.
             0
         } else {
-            ceil_div(MAX_TX_ERGS_LIMIT, LIMIT_FOR_REPEATED_WRITES_PUBDATA_HASHER)
+            ceil_div(
+                MAX_TX_ERGS_LIMIT,
+                capacities.limit_for_repeated_writes_pubdata_hasher,
+            )
         };
 
     let circuit_prices = vec![
         ergs_constant(
             "VM_CYCLE_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_PER_VM_SNAPSHOT),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_per_vm_snapshot),
         ),
         ergs_constant(
             "RAM_PERMUTATION_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_PER_RAM_PERMUTATION),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_per_ram_permutation),
         ),
         ergs_constant(
             "CODE_DECOMMITMENT_COST_PER_WORD_IN_ERGS",
-            // Each round of decommitter outputs 64 bytes of the code,
-            // while the user will pay for each word.
-            ceil_div(ERGS_PER_CIRCUIT, 2 * CYCLES_PER_CODE_DECOMMITTER),
+            ceil_div(ERGS_PER_CIRCUIT, code_decommitter_words_per_circuit),
         ),
         ergs_constant(
             "STORAGE_APPLICATION_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_PER_STORAGE_APPLICATION),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_per_storage_application),
         ),
         ergs_constant(
             "CODE_DECOMMITTER_SORTER_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_FOR_CODE_DECOMMITTER_SORTER),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_for_code_decommitter_sorter),
         ),
         ergs_constant(
             "LOG_DEMUXER_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_FOR_LOG_DEMUXER),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_for_log_demuxer),
         ),
         ergs_constant(
             "STORAGE_SORTER_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_FOR_STORAGE_SORTER),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_for_storage_sorter),
         ),
         ergs_constant(
             "EVENTS_OR_L1_MESSAGES_SORTER_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_FOR_EVENTS_OR_L1_MESSAGES_SORTER),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_for_events_or_l1_messages_sorter),
         ),
         ergs_constant(
             "INITIAL_WRITES_PUBDATA_HASHER_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, LIMIT_FOR_INITIAL_WRITES_PUBDATA_HASHER),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.limit_for_initial_writes_pubdata_hasher),
         ),
         ergs_constant(
             "REPEATED_WRITES_PUBDATA_HASHER_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, LIMIT_FOR_REPEATED_WRITES_PUBDATA_HASHER),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.limit_for_repeated_writes_pubdata_hasher),
         ),
         ergs_constant(
             "CODE_DECOMMITMENT_SORTER_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_FOR_CODE_DECOMMITTER_SORTER)
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_for_code_decommitter_sorter)
         ),
         comment("The following circuits are single-instance and so the provided prices are just minimal prices to preserve DDoS safety"),
         ergs_constant(
             "L1_MESSAGE_MIN_COST_IN_ERGS",
-            ceil_div(MAX_TX_ERGS_LIMIT, LIMIT_FOR_L1_MESSAGES_MERKLIZER),
+            ceil_div(MAX_TX_ERGS_LIMIT, capacities.limit_for_l1_messages_merklizer),
         ),
         ergs_constant(
             "INITIAL_WRITES_PUBDATA_HASHER_MIN_COST_IN_ERGS",
@@ -143,18 +192,385 @@ fn main() {
         comment("The following constants should not be used in the VM directly, but only in Solidity wrappers"),
         ergs_constant(
             "KECCAK256_CIRCUIT_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_PER_KECCAK256_CIRCUIT),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_per_keccak256_circuit),
         ),
         ergs_constant(
             "SHA256_CIRCUIT_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_PER_SHA256_CIRCUIT),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_per_sha256_circuit),
         ),
         ergs_constant(
             "ECRECOVER_CIRCUIT_COST_IN_ERGS",
-            ceil_div(ERGS_PER_CIRCUIT, CYCLES_PER_ECRECOVER_CIRCUIT),
+            ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_per_ecrecover_circuit),
         ),
     ]
     .concat();
 
-    save_circiut_prices(circuit_prices, "src/circuit_prices.rs");
+    Ok(format!(
+        "// This file is auto-generated, do not edit it manually\n// Any changes to this file require system upgrade!\n\n{}",
+        circuit_prices
+    ))
+}
+
+fn parse_ergs_constants(file_content: &str) -> BTreeMap<String, u32> {
+    let mut result = BTreeMap::new();
+    for line in file_content.lines() {
+        let Some(line) = line.strip_prefix("pub const ") else {
+            continue;
+        };
+        let Some((name, value)) = line.split_once(": u32 = ") else {
+            continue;
+        };
+        if let Ok(value) = value.trim_end_matches(';').parse::<u32>() {
+            result.insert(name.to_owned(), value);
+        }
+    }
+
+    result
+}
+
+/// Takes the pricing schedule the VM uses and replaces circuit prices in it with the provided set,
+/// so that we can show how opcode prices are affected by repricing.
+/// Returns `None` if some of the required constants are missing
+fn pricing_schedule_for_circuit_prices(
+    circuit_prices: &BTreeMap<String, u32>,
+) -> Option<PricingSchedule> {
+    let get = |name: &str| circuit_prices.get(name).copied();
+
+    let schedule = PricingSchedule {
+        vm_cycle_cost_in_ergs: get("VM_CYCLE_COST_IN_ERGS")?,
+        ram_permutation_cost_in_ergs: get("RAM_PERMUTATION_COST_IN_ERGS")?,
+        code_decommitment_cost_per_word_in_ergs: get("CODE_DECOMMITMENT_COST_PER_WORD_IN_ERGS")?,
+        log_demuxer_cost_in_ergs: get("LOG_DEMUXER_COST_IN_ERGS")?,
+        storage_sorter_cost_in_ergs: get("STORAGE_SORTER_COST_IN_ERGS")?,
+        events_or_l1_messages_sorter_cost_in_ergs: get(
+            "EVENTS_OR_L1_MESSAGES_SORTER_COST_IN_ERGS",
+        )?,
+        code_decommitment_sorter_cost_in_ergs: get("CODE_DECOMMITMENT_SORTER_COST_IN_ERGS")?,
+        l1_message_min_cost_in_ergs: get("L1_MESSAGE_MIN_COST_IN_ERGS")?,
+        storage_write_hasher_min_cost_in_ergs: get("STORAGE_WRITE_HASHER_MIN_COST_IN_ERGS")?,
+        ..PricingSchedule::default()
+    };
+
+    Some(schedule)
+}
+
+fn all_distinct_opcodes() -> BTreeSet<Opcode> {
+    OPCODES_TABLE.iter().map(|el| el.opcode).collect()
+}
+
+fn format_diff(old: &BTreeMap<String, u32>, new: &BTreeMap<String, u32>) -> String {
+    use std::fmt::Write;
+
+    let mut result = String::new();
+    let mut num_changes = 0;
+    for (name, new_value) in new.iter() {
+        match old.get(name) {
+            Some(old_value) if old_value == new_value => {}
+            Some(old_value) => {
+                writeln!(result, "{}: {} -> {}", name, old_value, new_value).unwrap();
+                num_changes += 1;
+            }
+            None => {
+                writeln!(result, "{}: added with value {}", name, new_value).unwrap();
+                num_changes += 1;
+            }
+        }
+    }
+    for (name, old_value) in old.iter() {
+        if !new.contains_key(name) {
+            writeln!(result, "{}: removed, was {}", name, old_value).unwrap();
+            num_changes += 1;
+        }
+    }
+
+    if num_changes == 0 {
+        return "No ergs constants changed\n".to_owned();
+    }
+
+    let format_price = |price: Option<u32>| match price {
+        Some(price) => price.to_string(),
+        None => "unknown".to_owned(),
+    };
+    let old_schedule = pricing_schedule_for_circuit_prices(old);
+    let new_schedule = pricing_schedule_for_circuit_prices(new);
+    let mut header_printed = false;
+    for opcode in all_distinct_opcodes().iter() {
+        let old_price = old_schedule.map(|el| opcode.ergs_price_with(&el));
+        let new_price = new_schedule.map(|el| opcode.ergs_price_with(&el));
+        if old_price == new_price {
+            continue;
+        }
+        if !header_printed {
+            writeln!(result, "\nDerived opcode prices:").unwrap();
+            header_printed = true;
+        }
+        writeln!(
+            result,
+            "{:?}: {} -> {}",
+            opcode,
+            format_price(old_price),
+            format_price(new_price)
+        )
+        .unwrap();
+    }
+
+    result
+}
+
+const USAGE: &str = "Usage: circuit_pricing_generator [--config <file>] [--output <file>] [--check]
+
+    --config <file>   read circuit capacities from a flat TOML or JSON (by extension) file,
+                      capacities that are not listed keep their defaults
+    --output <file>   path of the generated file, `src/circuit_prices.rs` of this crate by default
+    --check           do not write anything, exit with non-zero code if the file is outdated";
+
+struct Args {
+    config: Option<PathBuf>,
+    output: PathBuf,
+    check: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        config: None,
+        output: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/circuit_prices.rs"),
+        check: false,
+    };
+
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--config" => {
+                let path = it.next().ok_or("`--config` requires a path")?;
+                args.config = Some(PathBuf::from(path));
+            }
+            "--output" => {
+                let path = it.next().ok_or("`--output` requires a path")?;
+                args.output = PathBuf::from(path);
+            }
+            "--check" => args.check = true,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+
+    Ok(args)
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|err| {
+        eprintln!("{}\n\n{}", err, USAGE);
+        std::process::exit(2);
+    });
+
+    let capacities = match args.config.as_ref() {
+        Some(path) => {
+            let contents = std::fs::read_to_string(path)
+                .unwrap_or_else(|err| panic!("Unable to read {}: {}", path.display(), err));
+            let is_json = path.extension().map(|el| el == "json").unwrap_or(false);
+            capacities_from_file_contents(&contents, is_json).unwrap_or_else(|err| {
+                eprintln!("Invalid config {}: {}", path.display(), err);
+                std::process::exit(2);
+            })
+        }
+        None => CircuitCapacities::default(),
+    };
+
+    let file_content = generate_circiut_prices(&capacities).unwrap_or_else(|err| {
+        eprintln!("Invalid capacities: {}", err);
+        std::process::exit(2);
+    });
+    let existing_content = std::fs::read_to_string(&args.output).ok();
+
+    let old_constants = existing_content
+        .as_deref()
+        .map(parse_ergs_constants)
+        .unwrap_or_default();
+    let new_constants = parse_ergs_constants(&file_content);
+    print!("{}", format_diff(&old_constants, &new_constants));
+
+    if args.check {
+        if existing_content.as_deref() != Some(file_content.as_str()) {
+            eprintln!("{} is outdated", args.output.display());
+            std::process::exit(1);
+        }
+        println!("{} is up to date", args.output.display());
+    } else {
+        std::fs::write(&args.output, file_content).expect("Unable to write data");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use zkevm_opcode_defs::circuit_prices::{
+        ECRECOVER_CIRCUIT_COST_IN_ERGS, VM_CYCLE_COST_IN_ERGS,
+    };
+
+    #[test]
+    fn parse_capacities() {
+        let expected = CircuitCapacities {
+            cycles_per_vm_snapshot: 1000,
+            limit_for_l1_messages_merklizer: 100,
+            ..CircuitCapacities::default()
+        };
+
+        let toml =
+            "# comment\nCYCLES_PER_VM_SNAPSHOT = 1_000\nlimit_for_l1_messages_merklizer = 100\n";
+        assert_eq!(capacities_from_file_contents(toml, false), Ok(expected));
+
+        let json = r#"{"CYCLES_PER_VM_SNAPSHOT": 1000, "LIMIT_FOR_L1_MESSAGES_MERKLIZER": 100}"#;
+        assert_eq!(capacities_from_file_contents(json, true), Ok(expected));
+
+        assert_eq!(
+            capacities_from_file_contents("", false),
+            Ok(CircuitCapacities::default())
+        );
+        assert_eq!(
+            capacities_from_file_contents("{}", true),
+            Ok(CircuitCapacities::default())
+        );
+    }
+
+    #[test]
+    fn reject_invalid_capacities() {
+        for toml in [
+            // entries of tables are not capacities
+            "[prover]\nCYCLES_PER_VM_SNAPSHOT = 1000\n",
+            "CYCLES_PER_VM_SNAPSHOT = [1000, 2000]\n",
+            "CYCLES_PER_VM_SNAPSHOT = \"1000\"\n",
+            "CYCLES_PER_VM_SNAPSHOT = -1\n",
+            "CYCLES_PER_VM_SNAPSHOT = 0\n",
+            "CYCLES_PER_VM_SNAPSHOT = 1000\nCYCLES_PER_VM_SNAPSHOT = 2000\n",
+            "UNKNOWN_CAPACITY = 1000\n",
+            "CYCLES_PER_VM_SNAPSHOT 1000\n",
+        ] {
+            assert!(
+                capacities_from_file_contents(toml, false).is_err(),
+                "{}",
+                toml
+            );
+        }
+
+        for json in [
+            r#"{"CYCLES_PER_VM_SNAPSHOT": 1000,}"#,
+            r#"{"CYCLES_PER_VM_SNAPSHOT": "1,000"}"#,
+            r#"{"CYCLES_PER_VM_SNAPSHOT": 1000.5}"#,
+            r#"{"CYCLES_PER_VM_SNAPSHOT": {"value": 1000}}"#,
+            r#"{"CYCLES_PER_VM_SNAPSHOT": 0}"#,
+            r#"{"UNKNOWN_CAPACITY": 1000}"#,
+            r#"[1000]"#,
+        ] {
+            assert!(
+                capacities_from_file_contents(json, true).is_err(),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn reject_out_of_range_capacities() {
+        let json = r#"{"cycles_per_code_decommitter": 3000000000}"#;
+        let capacities = capacities_from_file_contents(json, true).unwrap();
+        assert!(generate_circiut_prices(&capacities).is_err());
+
+        for capacities in [
+            CircuitCapacities {
+                limit_for_initial_writes_pubdata_hasher: u32::MAX / 2,
+                ..CircuitCapacities::default()
+            },
+            CircuitCapacities {
+                limit_for_repeated_writes_pubdata_hasher: u32::MAX / 2,
+                ..CircuitCapacities::default()
+            },
+        ] {
+            assert!(generate_circiut_prices(&capacities).is_err());
+        }
+
+        // prices of the largest capacities round up to a single erg
+        let capacities = CircuitCapacities {
+            cycles_per_vm_snapshot: u32::MAX,
+            limit_for_l1_messages_merklizer: u32::MAX,
+            ..CircuitCapacities::default()
+        };
+        let constants = parse_ergs_constants(&generate_circiut_prices(&capacities).unwrap());
+        assert_eq!(constants.get("VM_CYCLE_COST_IN_ERGS"), Some(&1));
+        assert_eq!(constants.get("L1_MESSAGE_MIN_COST_IN_ERGS"), Some(&1));
+    }
+
+    #[test]
+    fn checked_in_prices_are_up_to_date() {
+        let generated = generate_circiut_prices(&CircuitCapacities::default()).unwrap();
+        let checked_in = include_str!("../circuit_prices.rs");
+        assert_eq!(generated, checked_in);
+
+        let constants = parse_ergs_constants(checked_in);
+        assert_eq!(
+            constants.get("VM_CYCLE_COST_IN_ERGS"),
+            Some(&VM_CYCLE_COST_IN_ERGS)
+        );
+        assert_eq!(
+            format_diff(&constants, &constants),
+            "No ergs constants changed\n"
+        );
+    }
+
+    #[test]
+    fn diff_lists_changed_constants_and_prices() {
+        let old = parse_ergs_constants(include_str!("../circuit_prices.rs"));
+        let capacities = CircuitCapacities {
+            cycles_per_vm_snapshot: CircuitCapacities::default().cycles_per_vm_snapshot / 2,
+            ..CircuitCapacities::default()
+        };
+        let new_cycle_cost = ceil_div(ERGS_PER_CIRCUIT, capacities.cycles_per_vm_snapshot);
+        assert!(new_cycle_cost > VM_CYCLE_COST_IN_ERGS);
+        let mut new = parse_ergs_constants(&generate_circiut_prices(&capacities).unwrap());
+        new.remove("ECRECOVER_CIRCUIT_COST_IN_ERGS");
+        new.insert("NEW_CIRCUIT_COST_IN_ERGS".to_owned(), 1);
+
+        let diff = format_diff(&old, &new);
+        let mut lines = diff.lines();
+        assert_eq!(
+            lines.next(),
+            Some("NEW_CIRCUIT_COST_IN_ERGS: added with value 1")
+        );
+        assert_eq!(
+            lines.next(),
+            Some(
+                format!(
+                    "VM_CYCLE_COST_IN_ERGS: {} -> {}",
+                    VM_CYCLE_COST_IN_ERGS, new_cycle_cost
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(
+            lines.next(),
+            Some(
+                format!(
+                    "ECRECOVER_CIRCUIT_COST_IN_ERGS: removed, was {}",
+                    ECRECOVER_CIRCUIT_COST_IN_ERGS
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(lines.next(), Some(""));
+        assert_eq!(lines.next(), Some("Derived opcode prices:"));
+
+        // every opcode costs at least one cycle
+        let nop = Opcode::Nop(NopOpcode);
+        let new_schedule = pricing_schedule_for_circuit_prices(&new).unwrap();
+        let expected = format!(
+            "{:?}: {} -> {}",
+            nop,
+            nop.ergs_price(),
+            nop.ergs_price_with(&new_schedule)
+        );
+        assert!(diff.lines().any(|el| el == expected), "{}", diff);
+        assert!(lines.all(|el| el.contains(" -> ")), "{}", diff);
+    }
 }
@@ -0,0 +1,431 @@
+use crate::decoding::VmEncodingMode;
+use crate::{DecodedOpcode, Opcode, Operand, UMAOpcode};
+
+/// The number of input "units" the corresponding circuits could take.
+/// It is assumed that the actual capacity of the circuits
+/// below is greater or equal to the values provided there.
+/// Some margin is suggested to not conduct a reprice upon every minor prover change.
+pub const CYCLES_PER_VM_SNAPSHOT: u32 = 23000;
+pub const CYCLES_PER_RAM_PERMUTATION: u32 = 260000;
+pub const CYCLES_PER_CODE_DECOMMITTER: u32 = 12100;
+pub const CYCLES_PER_STORAGE_APPLICATION: u32 = 118;
+pub const CYCLES_PER_KECCAK256_CIRCUIT: u32 = 2050;
+pub const CYCLES_PER_SHA256_CIRCUIT: u32 = 11500;
+pub const CYCLES_PER_ECRECOVER_CIRCUIT: u32 = 72;
+pub const CYCLES_FOR_CODE_DECOMMITTER_SORTER: u32 = 192500;
+pub const CYCLES_FOR_LOG_DEMUXER: u32 = 101500;
+pub const CYCLES_FOR_STORAGE_SORTER: u32 = 79000;
+pub const CYCLES_FOR_EVENTS_OR_L1_MESSAGES_SORTER: u32 = 88000;
+
+/// This kinds of circuit will always remain single-instance
+pub const LIMIT_FOR_L1_MESSAGES_MERKLIZER: u32 = 512;
+pub const LIMIT_FOR_INITIAL_WRITES_PUBDATA_HASHER: u32 = 4600;
+pub const LIMIT_FOR_REPEATED_WRITES_PUBDATA_HASHER: u32 = 7400;
+
+/// Capacities of the circuits in units, defaults to the constants above
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CircuitCapacities {
+    pub cycles_per_vm_snapshot: u32,
+    pub cycles_per_ram_permutation: u32,
+    pub cycles_per_code_decommitter: u32,
+    pub cycles_per_storage_application: u32,
+    pub cycles_per_keccak256_circuit: u32,
+    pub cycles_per_sha256_circuit: u32,
+    pub cycles_per_ecrecover_circuit: u32,
+    pub cycles_for_code_decommitter_sorter: u32,
+    pub cycles_for_log_demuxer: u32,
+    pub cycles_for_storage_sorter: u32,
+    pub cycles_for_events_or_l1_messages_sorter: u32,
+    pub limit_for_l1_messages_merklizer: u32,
+    pub limit_for_initial_writes_pubdata_hasher: u32,
+    pub limit_for_repeated_writes_pubdata_hasher: u32,
+}
+
+impl Default for CircuitCapacities {
+    fn default() -> Self {
+        Self {
+            cycles_per_vm_snapshot: CYCLES_PER_VM_SNAPSHOT,
+            cycles_per_ram_permutation: CYCLES_PER_RAM_PERMUTATION,
+            cycles_per_code_decommitter: CYCLES_PER_CODE_DECOMMITTER,
+            cycles_per_storage_application: CYCLES_PER_STORAGE_APPLICATION,
+            cycles_per_keccak256_circuit: CYCLES_PER_KECCAK256_CIRCUIT,
+            cycles_per_sha256_circuit: CYCLES_PER_SHA256_CIRCUIT,
+            cycles_per_ecrecover_circuit: CYCLES_PER_ECRECOVER_CIRCUIT,
+            cycles_for_code_decommitter_sorter: CYCLES_FOR_CODE_DECOMMITTER_SORTER,
+            cycles_for_log_demuxer: CYCLES_FOR_LOG_DEMUXER,
+            cycles_for_storage_sorter: CYCLES_FOR_STORAGE_SORTER,
+            cycles_for_events_or_l1_messages_sorter: CYCLES_FOR_EVENTS_OR_L1_MESSAGES_SORTER,
+            limit_for_l1_messages_merklizer: LIMIT_FOR_L1_MESSAGES_MERKLIZER,
+            limit_for_initial_writes_pubdata_hasher: LIMIT_FOR_INITIAL_WRITES_PUBDATA_HASHER,
+            limit_for_repeated_writes_pubdata_hasher: LIMIT_FOR_REPEATED_WRITES_PUBDATA_HASHER,
+        }
+    }
+}
+
+/// Events that can not be derived from the opcodes trace alone. Storage accesses, events,
+/// L1 messages and precompile calls are the queries that reach the log demuxer, so
+/// reverted ones should be counted as well
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CircuitUsageEvents {
+    pub storage_reads: usize,
+    pub initial_storage_writes: usize,
+    pub repeated_storage_writes: usize,
+    pub events: usize,
+    pub l1_messages: usize,
+    pub precompile_calls: usize,
+    pub decommitments: usize,
+    pub decommitted_words: usize,
+    pub keccak256_rounds: usize,
+    pub sha256_rounds: usize,
+    pub ecrecover_calls: usize,
+    /// Memory reads and writes performed by precompiles
+    pub precompile_memory_queries: usize,
+}
+
+/// Either the number of units or the number of instances for every circuit type
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CircuitUsage {
+    pub vm_snapshot: usize,
+    pub ram_permutation: usize,
+    pub code_decommitter: usize,
+    pub storage_application: usize,
+    pub keccak256: usize,
+    pub sha256: usize,
+    pub ecrecover: usize,
+    pub code_decommitter_sorter: usize,
+    pub log_demuxer: usize,
+    pub storage_sorter: usize,
+    pub events_or_l1_messages_sorter: usize,
+    pub l1_messages_merklizer: usize,
+    pub initial_writes_pubdata_hasher: usize,
+    pub repeated_writes_pubdata_hasher: usize,
+}
+
+impl CircuitUsage {
+    /// Converts units into the number of circuit instances. Single-instance circuits
+    /// are reported as 1 if they are used at all. Returns `None` if the capacity
+    /// of any multi-instance circuit is zero
+    pub fn instances(&self, capacities: &CircuitCapacities) -> Option<Self> {
+        let instances = |units: usize, capacity: u32| -> Option<usize> {
+            (capacity != 0).then(|| units.div_ceil(capacity as usize))
+        };
+        let single = |units: usize| -> usize { core::cmp::min(units, 1) };
+
+        Some(Self {
+            vm_snapshot: instances(self.vm_snapshot, capacities.cycles_per_vm_snapshot)?,
+            ram_permutation: instances(
+                self.ram_permutation,
+                capacities.cycles_per_ram_permutation,
+            )?,
+            code_decommitter: instances(
+                self.code_decommitter,
+                capacities.cycles_per_code_decommitter,
+            )?,
+            storage_application: instances(
+                self.storage_application,
+                capacities.cycles_per_storage_application,
+            )?,
+            keccak256: instances(self.keccak256, capacities.cycles_per_keccak256_circuit)?,
+            sha256: instances(self.sha256, capacities.cycles_per_sha256_circuit)?,
+            ecrecover: instances(self.ecrecover, capacities.cycles_per_ecrecover_circuit)?,
+            code_decommitter_sorter: instances(
+                self.code_decommitter_sorter,
+                capacities.cycles_for_code_decommitter_sorter,
+            )?,
+            log_demuxer: instances(self.log_demuxer, capacities.cycles_for_log_demuxer)?,
+            storage_sorter: instances(self.storage_sorter, capacities.cycles_for_storage_sorter)?,
+            events_or_l1_messages_sorter: instances(
+                self.events_or_l1_messages_sorter,
+                capacities.cycles_for_events_or_l1_messages_sorter,
+            )?,
+            l1_messages_merklizer: single(self.l1_messages_merklizer),
+            initial_writes_pubdata_hasher: single(self.initial_writes_pubdata_hasher),
+            repeated_writes_pubdata_hasher: single(self.repeated_writes_pubdata_hasher),
+        })
+    }
+
+    /// Whether the units exceed what single-instance circuits can take
+    pub fn exceeds_single_instance_limits(&self, capacities: &CircuitCapacities) -> bool {
+        self.l1_messages_merklizer > capacities.limit_for_l1_messages_merklizer as usize
+            || self.initial_writes_pubdata_hasher
+                > capacities.limit_for_initial_writes_pubdata_hasher as usize
+            || self.repeated_writes_pubdata_hasher
+                > capacities.limit_for_repeated_writes_pubdata_hasher as usize
+    }
+
+    pub fn total_instances(&self) -> usize {
+        self.vm_snapshot
+            + self.ram_permutation
+            + self.code_decommitter
+            + self.storage_application
+            + self.keccak256
+            + self.sha256
+            + self.ecrecover
+            + self.code_decommitter_sorter
+            + self.log_demuxer
+            + self.storage_sorter
+            + self.events_or_l1_messages_sorter
+            + self.l1_messages_merklizer
+            + self.initial_writes_pubdata_hasher
+            + self.repeated_writes_pubdata_hasher
+    }
+}
+
+/// Upper bound on the number of memory queries the opcode makes. Every opcode is
+/// counted as reading its own code word, even though 4 sequential opcodes share it
+pub fn memory_queries_for_opcode<const N: usize, E: VmEncodingMode<N>>(
+    opcode: &DecodedOpcode<N, E>,
+) -> usize {
+    let variant = &opcode.variant;
+    let mut num_queries = 1;
+    if let Operand::Full(src) = variant.src0_operand_type {
+        if src.is_memory_used() {
+            num_queries += 1;
+        }
+    }
+    if let Operand::Full(dst) = variant.dst0_operand_type {
+        if dst.is_memory_used() {
+            num_queries += 1;
+        }
+    }
+    // unaligned access is implemented with 2 aligned queries
+    match variant.opcode {
+        Opcode::UMA(UMAOpcode::HeapWrite) | Opcode::UMA(UMAOpcode::AuxHeapWrite) => {
+            num_queries += 4;
+        }
+        Opcode::UMA(_) => {
+            num_queries += 2;
+        }
+        _ => {}
+    }
+
+    num_queries
+}
+
+/// Accumulates circuit units from the executed opcodes and events, so that the batch
+/// can be sealed before any of the circuits overflows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CircuitUsageEstimator {
+    units: CircuitUsage,
+}
+
+impl CircuitUsageEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_opcode<const N: usize, E: VmEncodingMode<N>>(
+        &mut self,
+        opcode: &DecodedOpcode<N, E>,
+    ) {
+        self.units.vm_snapshot += 1;
+        self.units.ram_permutation += memory_queries_for_opcode(opcode);
+    }
+
+    pub fn add_opcodes<'a, const N: usize, E: VmEncodingMode<N> + 'a>(
+        &mut self,
+        opcodes: impl IntoIterator<Item = &'a DecodedOpcode<N, E>>,
+    ) {
+        for opcode in opcodes.into_iter() {
+            self.add_opcode(opcode);
+        }
+    }
+
+    pub fn add_events(&mut self, events: &CircuitUsageEvents) {
+        let storage_writes = events.initial_storage_writes + events.repeated_storage_writes;
+        let storage_queries = events.storage_reads + storage_writes;
+        let log_queries =
+            storage_queries + events.events + events.l1_messages + events.precompile_calls;
+
+        let units = &mut self.units;
+        // decommitted code is written into memory, and each round of decommitter
+        // outputs 2 words, with bytecodes having an odd number of words
+        units.ram_permutation += events.decommitted_words + events.precompile_memory_queries;
+        units.code_decommitter += (events.decommitted_words + events.decommitments) / 2;
+        units.code_decommitter_sorter += events.decommitments;
+        units.storage_application += storage_queries;
+        units.log_demuxer += log_queries;
+        units.storage_sorter += storage_queries;
+        units.events_or_l1_messages_sorter += events.events + events.l1_messages;
+        units.l1_messages_merklizer += events.l1_messages;
+        units.initial_writes_pubdata_hasher += events.initial_storage_writes;
+        units.repeated_writes_pubdata_hasher += events.repeated_storage_writes;
+        units.keccak256 += events.keccak256_rounds;
+        units.sha256 += events.sha256_rounds;
+        units.ecrecover += events.ecrecover_calls;
+    }
+
+    pub fn units(&self) -> CircuitUsage {
+        self.units
+    }
+
+    pub fn instances(&self, capacities: &CircuitCapacities) -> Option<CircuitUsage> {
+        self.units.instances(capacities)
+    }
+}
+
+/// Estimates the number of instances of every circuit for the given trace with default capacities
+pub fn estimate_circuit_instances<'a, const N: usize, E: VmEncodingMode<N> + 'a>(
+    opcodes: impl IntoIterator<Item = &'a DecodedOpcode<N, E>>,
+    events: &CircuitUsageEvents,
+) -> CircuitUsage {
+    let mut estimator = CircuitUsageEstimator::new();
+    estimator.add_opcodes(opcodes);
+    estimator.add_events(events);
+
+    estimator
+        .instances(&CircuitCapacities::default())
+        .expect("default capacities are positive")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::decoding::EncodingModeProduction;
+    use crate::{ImmMemHandlerFlags, NOP_OPCODE_VARIANT};
+
+    #[test]
+    fn estimate_instances_for_trace() {
+        let nop: DecodedOpcode<8, EncodingModeProduction> = DecodedOpcode {
+            variant: *NOP_OPCODE_VARIANT,
+            ..Default::default()
+        };
+        let mut push = nop;
+        push.variant.dst0_operand_type = Operand::Full(ImmMemHandlerFlags::UseStackWithPushPop);
+        assert_eq!(memory_queries_for_opcode(&nop), 1);
+        assert_eq!(memory_queries_for_opcode(&push), 2);
+
+        let trace = vec![nop; CYCLES_PER_VM_SNAPSHOT as usize + 1];
+        let events = CircuitUsageEvents {
+            storage_reads: 1,
+            decommitments: 1,
+            decommitted_words: 3,
+            ..Default::default()
+        };
+        let instances = estimate_circuit_instances(trace.iter(), &events);
+
+        assert_eq!(instances.vm_snapshot, 2);
+        assert_eq!(instances.ram_permutation, 1);
+        assert_eq!(instances.code_decommitter, 1);
+        assert_eq!(instances.storage_sorter, 1);
+        assert_eq!(instances.keccak256, 0);
+        assert_eq!(instances.l1_messages_merklizer, 0);
+    }
+
+    #[test]
+    fn log_queries_reach_demuxer_and_sorters() {
+        let events = CircuitUsageEvents {
+            storage_reads: 2,
+            initial_storage_writes: 3,
+            repeated_storage_writes: 4,
+            events: 5,
+            l1_messages: 6,
+            precompile_calls: 7,
+            ..Default::default()
+        };
+        let mut estimator = CircuitUsageEstimator::new();
+        estimator.add_events(&events);
+        let units = estimator.units();
+
+        assert_eq!(units.log_demuxer, 2 + 3 + 4 + 5 + 6 + 7);
+        assert_eq!(units.storage_sorter, 2 + 3 + 4);
+        assert_eq!(units.storage_application, 2 + 3 + 4);
+        assert_eq!(units.events_or_l1_messages_sorter, 5 + 6);
+        assert_eq!(units.l1_messages_merklizer, 6);
+        assert_eq!(units.initial_writes_pubdata_hasher, 3);
+        assert_eq!(units.repeated_writes_pubdata_hasher, 4);
+
+        let capacities = CircuitCapacities {
+            cycles_for_log_demuxer: 10,
+            cycles_for_storage_sorter: 9,
+            cycles_for_events_or_l1_messages_sorter: 10,
+            ..CircuitCapacities::default()
+        };
+        let instances = estimator.instances(&capacities).unwrap();
+        assert_eq!(instances.log_demuxer, 3);
+        assert_eq!(instances.storage_sorter, 1);
+        assert_eq!(instances.events_or_l1_messages_sorter, 2);
+    }
+
+    #[test]
+    fn precompiles_and_decommitments() {
+        let events = CircuitUsageEvents {
+            decommitments: 3,
+            decommitted_words: 7,
+            keccak256_rounds: 5,
+            sha256_rounds: 4,
+            ecrecover_calls: 3,
+            precompile_memory_queries: 11,
+            ..Default::default()
+        };
+        let mut estimator = CircuitUsageEstimator::new();
+        estimator.add_events(&events);
+        let units = estimator.units();
+
+        assert_eq!(units.code_decommitter, (7 + 3) / 2);
+        assert_eq!(units.code_decommitter_sorter, 3);
+        assert_eq!(units.ram_permutation, 7 + 11);
+        assert_eq!((units.keccak256, units.sha256, units.ecrecover), (5, 4, 3));
+
+        let capacities = CircuitCapacities {
+            cycles_per_keccak256_circuit: 2,
+            cycles_per_sha256_circuit: 4,
+            cycles_per_ecrecover_circuit: 1,
+            cycles_for_code_decommitter_sorter: 2,
+            ..CircuitCapacities::default()
+        };
+        let instances = estimator.instances(&capacities).unwrap();
+        assert_eq!(
+            (instances.keccak256, instances.sha256, instances.ecrecover),
+            (3, 1, 3)
+        );
+        assert_eq!(instances.code_decommitter_sorter, 2);
+        assert_eq!(instances.log_demuxer, 0);
+    }
+
+    #[test]
+    fn single_instance_circuits() {
+        let units = CircuitUsage {
+            l1_messages_merklizer: 1000,
+            initial_writes_pubdata_hasher: 1,
+            repeated_writes_pubdata_hasher: 0,
+            ..Default::default()
+        };
+        let capacities = CircuitCapacities::default();
+        let instances = units.instances(&capacities).unwrap();
+
+        assert_eq!(instances.l1_messages_merklizer, 1);
+        assert_eq!(instances.initial_writes_pubdata_hasher, 1);
+        assert_eq!(instances.repeated_writes_pubdata_hasher, 0);
+        assert_eq!(instances.total_instances(), 2);
+        assert!(units.exceeds_single_instance_limits(&capacities));
+
+        let units = CircuitUsage {
+            l1_messages_merklizer: LIMIT_FOR_L1_MESSAGES_MERKLIZER as usize,
+            ..units
+        };
+        assert!(!units.exceeds_single_instance_limits(&capacities));
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let units = CircuitUsage::default();
+        assert_eq!(
+            units.instances(&CircuitCapacities::default()),
+            Some(CircuitUsage::default())
+        );
+
+        let capacities = CircuitCapacities {
+            cycles_for_log_demuxer: 0,
+            ..CircuitCapacities::default()
+        };
+        assert_eq!(units.instances(&capacities), None);
+
+        // single-instance circuits are not divided by their limits
+        let capacities = CircuitCapacities {
+            limit_for_l1_messages_merklizer: 0,
+            ..CircuitCapacities::default()
+        };
+        assert!(units.instances(&capacities).is_some());
+    }
+}
@@ -6,6 +6,7 @@ pub const FAR_CALL_CONSTRUCTOR_CALL_BYTE_IDX: usize = 30;
 pub const FAR_CALL_SYSTEM_CALL_BYTE_IDX: usize = 31;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum FarCallForwardPageType {
     UseHeap = 0,
@@ -61,4 +62,20 @@ impl FarCallABI {
             to_system,
         }
     }
+
+    pub const fn to_u256(self) -> U256 {
+        let mut result = self.memory_quasi_fat_pointer.to_u256();
+        let extra_data = u32::from_le_bytes([
+            self.forwarding_mode as u8,
+            self.shard_id,
+            self.constructor_call as u8,
+            self.to_system as u8,
+        ]);
+        result.0[3] = (self.ergs_passed as u64) | ((extra_data as u64) << 32);
+
+        result
+    }
 }
+
+#[cfg(feature = "serde")]
+crate::serde_utils::serde_as_u256!(FarCallABI);
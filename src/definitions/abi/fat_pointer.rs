@@ -94,3 +94,6 @@ impl FatPointer {
         result
     }
 }
+
+#[cfg(feature = "serde")]
+crate::serde_utils::serde_as_u256!(FatPointer);
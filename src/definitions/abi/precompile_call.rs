@@ -28,8 +28,21 @@ impl PrecompileCallABI {
             per_precompile_interpreted,
         }
     }
+
+    pub const fn to_u256(self) -> U256 {
+        let mut result = U256::zero();
+        result.0[0] = (self.input_memory_offset as u64) | ((self.input_memory_length as u64) << 32);
+        result.0[1] =
+            (self.output_memory_offset as u64) | ((self.output_memory_length as u64) << 32);
+        result.0[3] = self.per_precompile_interpreted;
+
+        result
+    }
 }
 
+#[cfg(feature = "serde")]
+crate::serde_utils::serde_as_u256!(PrecompileCallABI);
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PrecompileCallInnerABI {
     pub input_memory_offset: u32,
@@ -9,6 +9,7 @@ const _: () = if RET_FORWARDING_MODE_BYTE_IDX != super::far_call::FAR_CALL_FORWA
 };
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum RetForwardPageType {
     UseHeap = 0,
@@ -48,4 +49,15 @@ impl RetABI {
             page_forwarding_mode,
         }
     }
+
+    pub const fn to_u256(self) -> U256 {
+        let mut result = self.memory_quasi_fat_pointer.to_u256();
+        let extra_data = u32::from_le_bytes([self.page_forwarding_mode as u8, 0, 0, 0]);
+        result.0[3] = (extra_data as u64) << 32;
+
+        result
+    }
 }
+
+#[cfg(feature = "serde")]
+crate::serde_utils::serde_as_u256!(RetABI);
@@ -1,271 +1,190 @@
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::*;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum Opcode {
-    Invalid(InvalidOpcode),
-    Nop(NopOpcode),
-    Add(AddOpcode),
-    Sub(SubOpcode),
-    Mul(MulOpcode),
-    Div(DivOpcode),
-    Jump(JumpOpcode),
-    Context(ContextOpcode),
-    Shift(ShiftOpcode),
-    Binop(BinopOpcode),
-    Ptr(PtrOpcode),
-    NearCall(NearCallOpcode),
-    Log(LogOpcode),
-    FarCall(FarCallOpcode),
-    Ret(RetOpcode),
-    UMA(UMAOpcode),
+// Declares `Opcode` and dispatches the calls to the variants, so adding an opcode
+// only requires a new line in the invocation below
+macro_rules! define_opcodes {
+    ($($variant:ident($inner:ident) = $idx:literal),+ $(,)?) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum Opcode {
+            $($variant($inner)),+
+        }
+
+        impl Opcode {
+            pub fn price_breakdown_with(&self, schedule: &PricingSchedule) -> PriceBreakdown {
+                match self {
+                    $(Opcode::$variant(inner) => inner.price_breakdown_with(schedule)),+
+                }
+            }
+
+            pub fn materialize_subvariant_from_prototype(
+                &self,
+                idx: usize,
+                version: &ISAVersion,
+            ) -> Self {
+                match self {
+                    $(Opcode::$variant(_) => Opcode::$variant(
+                        $inner::from_variant_index_for_version(idx, version)
+                            .expect("must materialize"),
+                    )),+
+                }
+            }
+
+            pub const fn variant_idx(&self) -> usize {
+                match self {
+                    $(Opcode::$variant(_) => $idx),+
+                }
+            }
+
+            /// `Log.StorageWrite` for the opcodes with sub-variants and just `Nop` for others
+            pub fn mnemonic(&self) -> String {
+                match self {
+                    $(Opcode::$variant(sub) => match sub.variant_name() {
+                        Some(name) => format!("{}.{}", stringify!($variant), name),
+                        None => stringify!($variant).to_owned(),
+                    }),+
+                }
+            }
+
+            /// All opcodes and their sub-variants regardless of the ISA version
+            pub fn all_variants() -> Vec<Self> {
+                let mut result = vec![];
+                $(result.extend($inner::ALL_VARIANTS.iter().map(|el| Opcode::$variant(*el)));)+
+
+                result
+            }
+
+            pub fn materialize_subvariant_idx(&self) -> usize {
+                match self {
+                    $(Opcode::$variant(sub) => sub.variant_index()),+
+                }
+            }
+
+            pub fn is_available_for_version(&self, version: ISAVersion) -> bool {
+                match self {
+                    $(Opcode::$variant(sub) => sub.is_available_for_version(version)),+
+                }
+            }
+
+            pub fn requires_kernel_mode(&self) -> bool {
+                match self {
+                    $(Opcode::$variant(sub) => sub.requires_kernel_mode()),+
+                }
+            }
+
+            pub fn can_be_used_in_static_context(&self) -> bool {
+                match self {
+                    $(Opcode::$variant(sub) => sub.can_be_used_in_static_context()),+
+                }
+            }
+
+            pub fn can_have_src0_from_mem(&self, version: ISAVersion) -> bool {
+                match self {
+                    $(Opcode::$variant(sub) => sub.can_have_src0_from_mem(version)),+
+                }
+            }
+
+            pub fn can_write_dst0_into_memory(&self, version: ISAVersion) -> bool {
+                match self {
+                    $(Opcode::$variant(sub) => sub.can_write_dst0_into_memory(version)),+
+                }
+            }
+
+            pub fn input_operands(&self, version: ISAVersion) -> Vec<Operand> {
+                match self {
+                    $(Opcode::$variant(sub) => sub.input_operands(version)),+
+                }
+            }
+
+            pub fn output_operands(&self, version: ISAVersion) -> Vec<Operand> {
+                match self {
+                    $(Opcode::$variant(sub) => sub.output_operands(version)),+
+                }
+            }
+        }
+    };
+}
+
+define_opcodes! {
+    Invalid(InvalidOpcode) = 0,
+    Nop(NopOpcode) = 1,
+    Add(AddOpcode) = 2,
+    Sub(SubOpcode) = 3,
+    Mul(MulOpcode) = 4,
+    Div(DivOpcode) = 5,
+    Jump(JumpOpcode) = 6,
+    Context(ContextOpcode) = 7,
+    Shift(ShiftOpcode) = 8,
+    Binop(BinopOpcode) = 9,
+    Ptr(PtrOpcode) = 10,
+    NearCall(NearCallOpcode) = 11,
+    Log(LogOpcode) = 12,
+    FarCall(FarCallOpcode) = 13,
+    Ret(RetOpcode) = 14,
+    UMA(UMAOpcode) = 15,
 }
 
 impl Opcode {
     pub fn ergs_price(&self) -> u32 {
-        match self {
-            Opcode::Invalid(inner) => inner.ergs_price(),
-            Opcode::Nop(inner) => inner.ergs_price(),
-            Opcode::Add(inner) => inner.ergs_price(),
-            Opcode::Sub(inner) => inner.ergs_price(),
-            Opcode::Mul(inner) => inner.ergs_price(),
-            Opcode::Div(inner) => inner.ergs_price(),
-            Opcode::Jump(inner) => inner.ergs_price(),
-            Opcode::Context(inner) => inner.ergs_price(),
-            Opcode::Shift(inner) => inner.ergs_price(),
-            Opcode::Binop(inner) => inner.ergs_price(),
-            Opcode::Ptr(inner) => inner.ergs_price(),
-            Opcode::NearCall(inner) => inner.ergs_price(),
-            Opcode::Log(inner) => inner.ergs_price(),
-            Opcode::FarCall(inner) => inner.ergs_price(),
-            Opcode::Ret(inner) => inner.ergs_price(),
-            Opcode::UMA(inner) => inner.ergs_price(),
-        }
+        self.ergs_price_with(&DEFAULT_PRICING_SCHEDULE)
     }
 
-    pub fn materialize_subvariant_from_prototype(&self, idx: usize, version: &ISAVersion) -> Self {
-        match self {
-            Opcode::Invalid(_) => Opcode::Invalid(
-                InvalidOpcode::from_variant_index_for_version(idx, version)
-                    .expect("must materialize"),
-            ),
-            Opcode::Nop(_) => Opcode::Nop(
-                NopOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::Add(_) => Opcode::Add(
-                AddOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::Sub(_) => Opcode::Sub(
-                SubOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::Mul(_) => Opcode::Mul(
-                MulOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::Div(_) => Opcode::Div(
-                DivOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::Jump(_) => Opcode::Jump(
-                JumpOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::Context(_) => Opcode::Context(
-                ContextOpcode::from_variant_index_for_version(idx, version)
-                    .expect("must materialize"),
-            ),
-            Opcode::Shift(_) => Opcode::Shift(
-                ShiftOpcode::from_variant_index_for_version(idx, version)
-                    .expect("must materialize"),
-            ),
-            Opcode::Binop(_) => Opcode::Binop(
-                BinopOpcode::from_variant_index_for_version(idx, version)
-                    .expect("must materialize"),
-            ),
-            Opcode::Ptr(_) => Opcode::Ptr(
-                PtrOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::NearCall(_) => Opcode::NearCall(
-                NearCallOpcode::from_variant_index_for_version(idx, version)
-                    .expect("must materialize"),
-            ),
-            Opcode::Log(_) => Opcode::Log(
-                LogOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::FarCall(_) => Opcode::FarCall(
-                FarCallOpcode::from_variant_index_for_version(idx, version)
-                    .expect("must materialize"),
-            ),
-            Opcode::Ret(_) => Opcode::Ret(
-                RetOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-            Opcode::UMA(_) => Opcode::UMA(
-                UMAOpcode::from_variant_index_for_version(idx, version).expect("must materialize"),
-            ),
-        }
+    pub fn ergs_price_with(&self, schedule: &PricingSchedule) -> u32 {
+        self.price_breakdown_with(schedule).total()
     }
 
-    pub const fn variant_idx(&self) -> usize {
-        match self {
-            Opcode::Invalid(_) => 0,
-            Opcode::Nop(_) => 1,
-            Opcode::Add(_) => 2,
-            Opcode::Sub(_) => 3,
-            Opcode::Mul(_) => 4,
-            Opcode::Div(_) => 5,
-            Opcode::Jump(_) => 6,
-            Opcode::Context(_) => 7,
-            Opcode::Shift(_) => 8,
-            Opcode::Binop(_) => 9,
-            Opcode::Ptr(_) => 10,
-            Opcode::NearCall(_) => 11,
-            Opcode::Log(_) => 12,
-            Opcode::FarCall(_) => 13,
-            Opcode::Ret(_) => 14,
-            Opcode::UMA(_) => 15,
-        }
+    pub fn price_breakdown(&self) -> PriceBreakdown {
+        self.price_breakdown_with(&DEFAULT_PRICING_SCHEDULE)
     }
 
-    pub fn materialize_subvariant_idx(&self) -> usize {
-        match self {
-            Opcode::Invalid(sub) => sub.variant_index(),
-            Opcode::Nop(sub) => sub.variant_index(),
-            Opcode::Add(sub) => sub.variant_index(),
-            Opcode::Sub(sub) => sub.variant_index(),
-            Opcode::Mul(sub) => sub.variant_index(),
-            Opcode::Div(sub) => sub.variant_index(),
-            Opcode::Jump(sub) => sub.variant_index(),
-            Opcode::Context(sub) => sub.variant_index(),
-            Opcode::Shift(sub) => sub.variant_index(),
-            Opcode::Binop(sub) => sub.variant_index(),
-            Opcode::Ptr(sub) => sub.variant_index(),
-            Opcode::NearCall(sub) => sub.variant_index(),
-            Opcode::Log(sub) => sub.variant_index(),
-            Opcode::FarCall(sub) => sub.variant_index(),
-            Opcode::Ret(sub) => sub.variant_index(),
-            Opcode::UMA(sub) => sub.variant_index(),
-        }
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        Self::all_variants()
+            .into_iter()
+            .find(|el| el.mnemonic() == mnemonic)
     }
+}
 
-    pub fn requires_kernel_mode(&self) -> bool {
-        match self {
-            Opcode::Invalid(sub) => sub.requires_kernel_mode(),
-            Opcode::Nop(sub) => sub.requires_kernel_mode(),
-            Opcode::Add(sub) => sub.requires_kernel_mode(),
-            Opcode::Sub(sub) => sub.requires_kernel_mode(),
-            Opcode::Mul(sub) => sub.requires_kernel_mode(),
-            Opcode::Div(sub) => sub.requires_kernel_mode(),
-            Opcode::Jump(sub) => sub.requires_kernel_mode(),
-            Opcode::Context(sub) => sub.requires_kernel_mode(),
-            Opcode::Shift(sub) => sub.requires_kernel_mode(),
-            Opcode::Binop(sub) => sub.requires_kernel_mode(),
-            Opcode::Ptr(sub) => sub.requires_kernel_mode(),
-            Opcode::NearCall(sub) => sub.requires_kernel_mode(),
-            Opcode::Log(sub) => sub.requires_kernel_mode(),
-            Opcode::FarCall(sub) => sub.requires_kernel_mode(),
-            Opcode::Ret(sub) => sub.requires_kernel_mode(),
-            Opcode::UMA(sub) => sub.requires_kernel_mode(),
-        }
-    }
-
-    pub fn can_be_used_in_static_context(&self) -> bool {
-        match self {
-            Opcode::Invalid(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Nop(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Add(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Sub(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Mul(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Div(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Jump(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Context(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Shift(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Binop(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Ptr(sub) => sub.can_be_used_in_static_context(),
-            Opcode::NearCall(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Log(sub) => sub.can_be_used_in_static_context(),
-            Opcode::FarCall(sub) => sub.can_be_used_in_static_context(),
-            Opcode::Ret(sub) => sub.can_be_used_in_static_context(),
-            Opcode::UMA(sub) => sub.can_be_used_in_static_context(),
-        }
+// opcodes are stored by their mnemonics, so the representation doesn't depend on the encoding
+#[cfg(feature = "serde")]
+impl serde::Serialize for Opcode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.mnemonic())
     }
+}
 
-    pub fn can_have_src0_from_mem(&self, version: ISAVersion) -> bool {
-        match self {
-            Opcode::Invalid(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Nop(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Add(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Sub(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Mul(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Div(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Jump(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Context(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Shift(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Binop(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Ptr(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::NearCall(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Log(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::FarCall(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::Ret(sub) => sub.can_have_src0_from_mem(version),
-            Opcode::UMA(sub) => sub.can_have_src0_from_mem(version),
-        }
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Opcode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mnemonic = String::deserialize(deserializer)?;
+        Opcode::from_mnemonic(&mnemonic).ok_or_else(|| {
+            serde::de::Error::custom(format!("unknown opcode mnemonic `{}`", mnemonic))
+        })
     }
+}
 
-    pub fn can_write_dst0_into_memory(&self, version: ISAVersion) -> bool {
-        match self {
-            Opcode::Invalid(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Nop(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Add(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Sub(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Mul(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Div(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Jump(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Context(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Shift(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Binop(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Ptr(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::NearCall(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Log(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::FarCall(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::Ret(sub) => sub.can_write_dst0_into_memory(version),
-            Opcode::UMA(sub) => sub.can_write_dst0_into_memory(version),
-        }
-    }
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    pub fn input_operands(&self, version: ISAVersion) -> Vec<Operand> {
-        match self {
-            Opcode::Invalid(sub) => sub.input_operands(version),
-            Opcode::Nop(sub) => sub.input_operands(version),
-            Opcode::Add(sub) => sub.input_operands(version),
-            Opcode::Sub(sub) => sub.input_operands(version),
-            Opcode::Mul(sub) => sub.input_operands(version),
-            Opcode::Div(sub) => sub.input_operands(version),
-            Opcode::Jump(sub) => sub.input_operands(version),
-            Opcode::Context(sub) => sub.input_operands(version),
-            Opcode::Shift(sub) => sub.input_operands(version),
-            Opcode::Binop(sub) => sub.input_operands(version),
-            Opcode::Ptr(sub) => sub.input_operands(version),
-            Opcode::NearCall(sub) => sub.input_operands(version),
-            Opcode::Log(sub) => sub.input_operands(version),
-            Opcode::FarCall(sub) => sub.input_operands(version),
-            Opcode::Ret(sub) => sub.input_operands(version),
-            Opcode::UMA(sub) => sub.input_operands(version),
+    #[test]
+    fn mnemonics_are_unique() {
+        let all = Opcode::all_variants();
+        for el in all.iter() {
+            assert_eq!(Opcode::from_mnemonic(&el.mnemonic()), Some(*el));
         }
-    }
+        let unique: alloc::collections::BTreeSet<_> = all.iter().map(|el| el.mnemonic()).collect();
+        assert_eq!(unique.len(), all.len());
 
-    pub fn output_operands(&self, version: ISAVersion) -> Vec<Operand> {
-        match self {
-            Opcode::Invalid(sub) => sub.output_operands(version),
-            Opcode::Nop(sub) => sub.output_operands(version),
-            Opcode::Add(sub) => sub.output_operands(version),
-            Opcode::Sub(sub) => sub.output_operands(version),
-            Opcode::Mul(sub) => sub.output_operands(version),
-            Opcode::Div(sub) => sub.output_operands(version),
-            Opcode::Jump(sub) => sub.output_operands(version),
-            Opcode::Context(sub) => sub.output_operands(version),
-            Opcode::Shift(sub) => sub.output_operands(version),
-            Opcode::Binop(sub) => sub.output_operands(version),
-            Opcode::Ptr(sub) => sub.output_operands(version),
-            Opcode::NearCall(sub) => sub.output_operands(version),
-            Opcode::Log(sub) => sub.output_operands(version),
-            Opcode::FarCall(sub) => sub.output_operands(version),
-            Opcode::Ret(sub) => sub.output_operands(version),
-            Opcode::UMA(sub) => sub.output_operands(version),
-        }
+        assert_eq!(Opcode::Nop(NopOpcode).mnemonic(), "Nop");
+        assert_eq!(
+            Opcode::Log(LogOpcode::StorageWrite).mnemonic(),
+            "Log.StorageWrite"
+        );
+        assert_eq!(Opcode::from_mnemonic("Log"), None);
     }
 }
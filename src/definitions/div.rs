@@ -1,34 +1,29 @@
 use super::*;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct DivOpcode;
+opcode_variants! {
+    pub struct DivOpcode;
+}
 
 impl OpcodeVariantProps for DivOpcode {
-    fn all_variants() -> Vec<Self> {
-        vec![DivOpcode]
-    }
-
-    fn max_variant_idx_for_version(_version: ISAVersion) -> usize {
-        DivOpcode.variant_index()
-    }
-
-    fn minimal_version(&self) -> ISAVersion {
-        ALL_ISA_VERSIONS[0]
-    }
-
-    fn variant_index(&self) -> usize {
-        0
-    }
-
-    fn from_variant_index_for_version(index: usize, _version: &ISAVersion) -> Option<Self> {
-        match index {
-            0 => Some(DivOpcode),
-            _ => None,
-        }
-    }
-
-    fn ergs_price(&self) -> u32 {
-        RICH_ADDRESSING_OPCODE_ERGS
+    const ISA_DESCRIPTIONS: &'static [OpcodeVersionDescription] = &[OpcodeVersionDescription {
+        since: ISAVersion(0),
+        variants: &[VariantsDescription {
+            variant_indices: &[0],
+            num_non_exclusive_flags: 2,
+            num_used_immediates: 2,
+            input_operands: &[
+                Operand::Full(ImmMemHandlerFlags::UseRegOnly),
+                Operand::RegOnly,
+            ],
+            output_operands: &[
+                Operand::Full(ImmMemHandlerFlags::UseRegOnly),
+                Operand::RegOnly,
+            ],
+        }],
+    }];
+
+    fn price_breakdown_with(&self, schedule: &PricingSchedule) -> PriceBreakdown {
+        schedule.rich_addressing_opcode_breakdown()
     }
 }
 
@@ -36,32 +31,7 @@ impl OpcodeProps for DivOpcode {
     fn name(&self) -> &'static str {
         "Div opcode"
     }
-    fn variants_data(&self, version: ISAVersion) -> Vec<OpcodeVariantData> {
-        match version {
-            ISAVersion(0) => {
-                full_variants_product(0..=Self::max_variant_idx_for_version(version), 2, 2)
-            }
-            ISAVersion(1) => {
-                full_variants_product(0..=Self::max_variant_idx_for_version(version), 2, 2)
-            }
-            _ => unimplemented!(),
-        }
-    }
-    fn max_variant_idx(&self, _version: ISAVersion) -> usize {
-        DivOpcode.variant_index()
-    }
-    fn input_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        vec![
-            Operand::Full(ImmMemHandlerFlags::UseRegOnly),
-            Operand::RegOnly,
-        ]
-    }
-    fn output_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        vec![
-            Operand::Full(ImmMemHandlerFlags::UseRegOnly),
-            Operand::RegOnly,
-        ]
-    }
+    opcode_props_from_isa_descriptions!();
     fn requires_kernel_mode(&self) -> bool {
         false
     }
@@ -1,61 +1,34 @@
 use super::*;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct InvalidOpcode;
+opcode_variants! {
+    pub struct InvalidOpcode;
+}
 
 impl OpcodeVariantProps for InvalidOpcode {
-    fn all_variants() -> Vec<Self> {
-        vec![InvalidOpcode]
-    }
-
-    fn max_variant_idx_for_version(_version: ISAVersion) -> usize {
-        InvalidOpcode.variant_index()
-    }
-
-    fn minimal_version(&self) -> ISAVersion {
-        ALL_ISA_VERSIONS[0]
-    }
-
-    fn variant_index(&self) -> usize {
-        0
-    }
-
-    fn from_variant_index_for_version(index: usize, _version: &ISAVersion) -> Option<Self> {
-        match index {
-            0 => Some(InvalidOpcode),
-            _ => None,
+    const ISA_DESCRIPTIONS: &'static [OpcodeVersionDescription] = &[OpcodeVersionDescription {
+        since: ISAVersion(0),
+        variants: &[VariantsDescription {
+            variant_indices: &[0],
+            num_non_exclusive_flags: 0,
+            num_used_immediates: 0,
+            input_operands: &[],
+            output_operands: &[],
+        }],
+    }];
+
+    fn price_breakdown_with(&self, schedule: &PricingSchedule) -> PriceBreakdown {
+        PriceBreakdown {
+            invalid_opcode: schedule.invalid_opcode_ergs,
+            ..PriceBreakdown::default()
         }
     }
-
-    fn ergs_price(&self) -> u32 {
-        INVALID_OPCODE_ERGS
-    }
 }
 
 impl OpcodeProps for InvalidOpcode {
     fn name(&self) -> &'static str {
         "Invalid opcode"
     }
-    fn variants_data(&self, version: ISAVersion) -> Vec<OpcodeVariantData> {
-        match version {
-            ISAVersion(0) => {
-                full_variants_product(0..=Self::max_variant_idx_for_version(version), 0, 0)
-            }
-            ISAVersion(1) => {
-                full_variants_product(0..=Self::max_variant_idx_for_version(version), 0, 0)
-            }
-            _ => unimplemented!(),
-        }
-    }
-    fn max_variant_idx(&self, _version: ISAVersion) -> usize {
-        InvalidOpcode.variant_index()
-    }
-    fn input_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        vec![]
-    }
-    fn output_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        vec![]
-    }
+    opcode_props_from_isa_descriptions!();
     fn requires_kernel_mode(&self) -> bool {
         false
     }
@@ -0,0 +1,195 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::*;
+
+use crate::OPCODES_TABLE_WIDTH;
+
+/// Variant at some index of the decoding table that was changed between the versions
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReplacedVariant {
+    pub index: usize,
+    pub old: OpcodeVariant,
+    pub new: OpcodeVariant,
+    /// e.g. `RegOnly` -> `RegOrImm(UseRegOnly)` for the same opcode
+    pub semantically_equal: bool,
+}
+
+impl ReplacedVariant {
+    pub fn price_changed(&self) -> bool {
+        self.old.ergs_price() != self.new.ergs_price()
+    }
+
+    pub fn kernel_mode_changed(&self) -> bool {
+        self.old.requires_kernel_mode() != self.new.requires_kernel_mode()
+    }
+
+    pub fn static_context_changed(&self) -> bool {
+        self.old.can_be_used_in_static_context() != self.new.can_be_used_in_static_context()
+    }
+
+    pub fn properties_changed(&self) -> bool {
+        self.price_changed() || self.kernel_mode_changed() || self.static_context_changed()
+    }
+}
+
+/// Difference between the decoding tables of two ISA versions. Indices are
+/// the positions in the table of width `OPCODES_TABLE_WIDTH`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IsaVersionDiff {
+    pub from: ISAVersion,
+    pub to: ISAVersion,
+    pub added: Vec<(usize, OpcodeVariant)>,
+    pub removed: Vec<(usize, OpcodeVariant)>,
+    pub replaced: Vec<ReplacedVariant>,
+}
+
+impl IsaVersionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.replaced.is_empty()
+    }
+
+    /// Replacements that are not just a different encoding of the same variant
+    pub fn incompatible_replacements(&self) -> impl Iterator<Item = &ReplacedVariant> {
+        self.replaced
+            .iter()
+            .filter(|el| !el.semantically_equal || el.properties_changed())
+    }
+}
+
+/// Returns `None` if any of the versions is unknown
+pub fn diff_isa_versions(a: ISAVersion, b: ISAVersion) -> Option<IsaVersionDiff> {
+    if !ALL_ISA_VERSIONS.contains(&a) || !ALL_ISA_VERSIONS.contains(&b) {
+        return None;
+    }
+
+    let old_table = synthesize_opcode_decoding_tables(OPCODES_TABLE_WIDTH, a);
+    let new_table = synthesize_opcode_decoding_tables(OPCODES_TABLE_WIDTH, b);
+
+    let mut diff = IsaVersionDiff {
+        from: a,
+        to: b,
+        added: vec![],
+        removed: vec![],
+        replaced: vec![],
+    };
+
+    for (index, (old, new)) in old_table.iter().zip(new_table.iter()).enumerate() {
+        if old == new {
+            continue;
+        }
+
+        if old == &INVALID_OPCODE_VARIANT {
+            diff.added.push((index, *new));
+        } else if new == &INVALID_OPCODE_VARIANT {
+            diff.removed.push((index, *old));
+        } else {
+            diff.replaced.push(ReplacedVariant {
+                index,
+                old: *old,
+                new: *new,
+                semantically_equal: semantically_equal(old, new),
+            });
+        }
+    }
+
+    Some(diff)
+}
+
+fn short_description(variant: &OpcodeVariant) -> String {
+    format!(
+        "{:?}, src0: {:?}, dst0: {:?}, flags: {}|{}",
+        variant.opcode,
+        variant.src0_operand_type,
+        variant.dst0_operand_type,
+        variant.flags[0],
+        variant.flags[1]
+    )
+}
+
+impl core::fmt::Display for IsaVersionDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "ISA version {} -> {}", self.from.0, self.to.0)?;
+
+        writeln!(f, "Added variants: {}", self.added.len())?;
+        for (index, variant) in self.added.iter() {
+            writeln!(f, "  [{}] {}", index, short_description(variant))?;
+        }
+
+        writeln!(f, "Removed variants: {}", self.removed.len())?;
+        for (index, variant) in self.removed.iter() {
+            writeln!(f, "  [{}] {}", index, short_description(variant))?;
+        }
+
+        writeln!(f, "Replaced variants: {}", self.replaced.len())?;
+        for el in self.replaced.iter() {
+            writeln!(f, "  [{}] {}", el.index, short_description(&el.old))?;
+            writeln!(f, "    -> {}", short_description(&el.new))?;
+            if !el.semantically_equal {
+                writeln!(f, "    semantics changed")?;
+            }
+            if el.price_changed() {
+                writeln!(
+                    f,
+                    "    price: {} -> {}",
+                    el.old.ergs_price(),
+                    el.new.ergs_price()
+                )?;
+            }
+            if el.kernel_mode_changed() {
+                writeln!(
+                    f,
+                    "    requires kernel mode: {} -> {}",
+                    el.old.requires_kernel_mode(),
+                    el.new.requires_kernel_mode()
+                )?;
+            }
+            if el.static_context_changed() {
+                writeln!(
+                    f,
+                    "    can be used in static context: {} -> {}",
+                    el.old.can_be_used_in_static_context(),
+                    el.new.can_be_used_in_static_context()
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn diff_first_versions() {
+        let diff = diff_isa_versions(ISAVersion(0), ISAVersion(1)).unwrap();
+        assert_eq!(
+            diff.added.len(),
+            compute_encoding_density(ISAVersion(1)) - compute_encoding_density(ISAVersion(0))
+        );
+        assert!(diff.removed.is_empty());
+        assert!(!diff.replaced.is_empty());
+        assert_eq!(diff.incompatible_replacements().count(), 0);
+        for el in diff.replaced.iter() {
+            assert!(matches!(el.old.opcode, Opcode::UMA(_)));
+            assert_eq!(el.old.src0_operand_type, Operand::RegOnly);
+            assert_eq!(
+                el.new.src0_operand_type,
+                Operand::RegOrImm(RegOrImmFlags::UseRegOnly)
+            );
+        }
+
+        let reverse = diff_isa_versions(ISAVersion(1), ISAVersion(0)).unwrap();
+        assert_eq!(reverse.removed, diff.added);
+        assert_eq!(reverse.replaced.len(), diff.replaced.len());
+
+        assert!(diff_isa_versions(ISAVersion(1), ISAVersion(1))
+            .unwrap()
+            .is_empty());
+        assert!(diff_isa_versions(ISAVersion(0), ISAVersion(u8::MAX)).is_none());
+    }
+}
@@ -1,98 +1,107 @@
-use crate::{
-    circuit_prices::{
-        EVENTS_OR_L1_MESSAGES_SORTER_COST_IN_ERGS, L1_MESSAGE_MIN_COST_IN_ERGS,
-        LOG_DEMUXER_COST_IN_ERGS, RAM_PERMUTATION_COST_IN_ERGS, STORAGE_SORTER_COST_IN_ERGS,
-        VM_CYCLE_COST_IN_ERGS,
-    },
-    system_params::MIN_STORAGE_WRITE_COST,
-};
-
 use super::*;
 
 pub const FIRST_MESSAGE_FLAG_IDX: usize = 0;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[repr(u8)]
-pub enum LogOpcode {
-    StorageRead = 0,
-    StorageWrite,
-    ToL1Message,
-    Event,
-    PrecompileCall,
+opcode_variants! {
+    pub enum LogOpcode {
+        StorageRead,
+        StorageWrite,
+        ToL1Message,
+        Event,
+        PrecompileCall,
+    }
 }
 
 impl OpcodeVariantProps for LogOpcode {
-    fn all_variants() -> Vec<Self> {
-        vec![
-            LogOpcode::StorageRead,
-            LogOpcode::StorageWrite,
-            LogOpcode::ToL1Message,
-            LogOpcode::Event,
-            LogOpcode::PrecompileCall,
-        ]
-    }
-
-    fn max_variant_idx_for_version(_version: ISAVersion) -> usize {
-        LogOpcode::PrecompileCall.variant_index()
-    }
-
-    fn minimal_version(&self) -> ISAVersion {
-        ALL_ISA_VERSIONS[0]
-    }
+    const ISA_DESCRIPTIONS: &'static [OpcodeVersionDescription] = &[OpcodeVersionDescription {
+        since: ISAVersion(0),
+        variants: &[
+            VariantsDescription {
+                variant_indices: &[LogOpcode::StorageRead as usize],
+                num_non_exclusive_flags: 0,
+                num_used_immediates: 0,
+                input_operands: &[Operand::RegOnly],
+                output_operands: &[Operand::RegOnly],
+            },
+            VariantsDescription {
+                variant_indices: &[LogOpcode::StorageWrite as usize],
+                num_non_exclusive_flags: 0,
+                num_used_immediates: 0,
+                input_operands: &[Operand::RegOnly, Operand::RegOnly],
+                output_operands: &[],
+            },
+            // can be "initial"
+            VariantsDescription {
+                variant_indices: &[LogOpcode::ToL1Message as usize, LogOpcode::Event as usize],
+                num_non_exclusive_flags: 1,
+                num_used_immediates: 0,
+                input_operands: &[Operand::RegOnly, Operand::RegOnly],
+                output_operands: &[],
+            },
+            VariantsDescription {
+                variant_indices: &[LogOpcode::PrecompileCall as usize],
+                num_non_exclusive_flags: 0,
+                num_used_immediates: 0,
+                input_operands: &[Operand::RegOnly],
+                output_operands: &[Operand::RegOnly],
+            },
+        ],
+    }];
 
-    fn variant_index(&self) -> usize {
-        (*self as u8) as usize
-    }
-
-    fn from_variant_index_for_version(index: usize, _version: &ISAVersion) -> Option<Self> {
-        match index {
-            i if i == LogOpcode::StorageRead.variant_index() => Some(LogOpcode::StorageRead),
-            i if i == LogOpcode::StorageWrite.variant_index() => Some(LogOpcode::StorageWrite),
-            i if i == LogOpcode::ToL1Message.variant_index() => Some(LogOpcode::ToL1Message),
-            i if i == LogOpcode::Event.variant_index() => Some(LogOpcode::Event),
-            i if i == LogOpcode::PrecompileCall.variant_index() => Some(LogOpcode::PrecompileCall),
-            _ => None,
-        }
-    }
-
-    fn ergs_price(&self) -> u32 {
+    fn price_breakdown_with(&self, schedule: &PricingSchedule) -> PriceBreakdown {
         match self {
-            LogOpcode::StorageRead => {
-                STORAGE_READ_IO_PRICE
-                    + VM_CYCLE_COST_IN_ERGS
-                    + RAM_PERMUTATION_COST_IN_ERGS
-                    + LOG_DEMUXER_COST_IN_ERGS
-                    + STORAGE_SORTER_COST_IN_ERGS
-            }
+            LogOpcode::StorageRead => PriceBreakdown {
+                io: schedule.storage_read_io_price,
+                vm_cycles: schedule.vm_cycle_cost_in_ergs,
+                ram_permutations: schedule.ram_permutation_cost_in_ergs,
+                log_demuxer: schedule.log_demuxer_cost_in_ergs,
+                sorters: schedule.storage_sorter_cost_in_ergs,
+                ..PriceBreakdown::default()
+            },
             // If the write was not initial, the user will be refunded
             LogOpcode::StorageWrite => {
-                let intrinsic = STORAGE_WRITE_IO_PRICE
-                    + 2 * VM_CYCLE_COST_IN_ERGS
-                    + RAM_PERMUTATION_COST_IN_ERGS
-                    + 2 * LOG_DEMUXER_COST_IN_ERGS
-                    + 2 * STORAGE_SORTER_COST_IN_ERGS;
+                let intrinsic = PriceBreakdown {
+                    io: schedule.storage_write_io_price,
+                    vm_cycles: schedule.vm_cycle_cost_in_ergs.saturating_mul(2),
+                    ram_permutations: schedule.ram_permutation_cost_in_ergs,
+                    log_demuxer: schedule.log_demuxer_cost_in_ergs.saturating_mul(2),
+                    sorters: schedule.storage_sorter_cost_in_ergs.saturating_mul(2),
+                    ..PriceBreakdown::default()
+                };
 
-                std::cmp::max(intrinsic, MIN_STORAGE_WRITE_COST)
+                intrinsic.with_min_cost(schedule.min_storage_write_cost())
             }
-            // Note, that the `L1_MESSAGE_MIN_COST_IN_ERGS` is only needed for DDoS protection
+            // Note, that the `l1_message_min_cost_in_ergs` is only needed for DDoS protection
             LogOpcode::ToL1Message => {
-                let intrinsic_cost = L1_MESSAGE_IO_PRICE
-                    + 2 * VM_CYCLE_COST_IN_ERGS
-                    + RAM_PERMUTATION_COST_IN_ERGS
-                    + 2 * LOG_DEMUXER_COST_IN_ERGS
-                    + 2 * EVENTS_OR_L1_MESSAGES_SORTER_COST_IN_ERGS;
-                std::cmp::max(intrinsic_cost, L1_MESSAGE_MIN_COST_IN_ERGS)
-            }
-            LogOpcode::Event => {
-                EVENT_IO_PRICE
-                    + 2 * VM_CYCLE_COST_IN_ERGS
-                    + RAM_PERMUTATION_COST_IN_ERGS
-                    + 2 * LOG_DEMUXER_COST_IN_ERGS
-                    + 2 * EVENTS_OR_L1_MESSAGES_SORTER_COST_IN_ERGS
-            }
-            LogOpcode::PrecompileCall => {
-                VM_CYCLE_COST_IN_ERGS + RAM_PERMUTATION_COST_IN_ERGS + LOG_DEMUXER_COST_IN_ERGS
+                let intrinsic = PriceBreakdown {
+                    io: schedule.l1_message_io_price,
+                    vm_cycles: schedule.vm_cycle_cost_in_ergs.saturating_mul(2),
+                    ram_permutations: schedule.ram_permutation_cost_in_ergs,
+                    log_demuxer: schedule.log_demuxer_cost_in_ergs.saturating_mul(2),
+                    sorters: schedule
+                        .events_or_l1_messages_sorter_cost_in_ergs
+                        .saturating_mul(2),
+                    ..PriceBreakdown::default()
+                };
+
+                intrinsic.with_min_cost(schedule.l1_message_min_cost_in_ergs)
             }
+            LogOpcode::Event => PriceBreakdown {
+                io: schedule.event_io_price,
+                vm_cycles: schedule.vm_cycle_cost_in_ergs.saturating_mul(2),
+                ram_permutations: schedule.ram_permutation_cost_in_ergs,
+                log_demuxer: schedule.log_demuxer_cost_in_ergs.saturating_mul(2),
+                sorters: schedule
+                    .events_or_l1_messages_sorter_cost_in_ergs
+                    .saturating_mul(2),
+                ..PriceBreakdown::default()
+            },
+            LogOpcode::PrecompileCall => PriceBreakdown {
+                vm_cycles: schedule.vm_cycle_cost_in_ergs,
+                ram_permutations: schedule.ram_permutation_cost_in_ergs,
+                log_demuxer: schedule.log_demuxer_cost_in_ergs,
+                ..PriceBreakdown::default()
+            },
         }
     }
 }
@@ -101,64 +110,7 @@ impl OpcodeProps for LogOpcode {
     fn name(&self) -> &'static str {
         "Log opcode"
     }
-    fn variants_data(&self, version: ISAVersion) -> Vec<OpcodeVariantData> {
-        match version {
-            ISAVersion(0) | ISAVersion(1) => {
-                vec![
-                    // Storage read
-                    OpcodeVariantData {
-                        variant_idx: LogOpcode::StorageRead.variant_index(),
-                        num_non_exclusive_flags: 0,
-                        num_used_immediates: 0,
-                    },
-                    // Storage write
-                    OpcodeVariantData {
-                        variant_idx: LogOpcode::StorageWrite.variant_index(),
-                        num_non_exclusive_flags: 0,
-                        num_used_immediates: 0,
-                    },
-                    // L1 message
-                    OpcodeVariantData {
-                        variant_idx: LogOpcode::ToL1Message.variant_index(),
-                        num_non_exclusive_flags: 1, // can be "initial"
-                        num_used_immediates: 0,
-                    },
-                    // Event
-                    OpcodeVariantData {
-                        variant_idx: LogOpcode::Event.variant_index(),
-                        num_non_exclusive_flags: 1, // can be initial
-                        num_used_immediates: 0,
-                    },
-                    // Precompile calls
-                    OpcodeVariantData {
-                        variant_idx: LogOpcode::PrecompileCall.variant_index(),
-                        num_non_exclusive_flags: 0,
-                        num_used_immediates: 0,
-                    },
-                ]
-            }
-            _ => unimplemented!(),
-        }
-    }
-    fn max_variant_idx(&self, _version: ISAVersion) -> usize {
-        LogOpcode::PrecompileCall.variant_index()
-    }
-    fn input_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        match self {
-            LogOpcode::StorageWrite | LogOpcode::Event | LogOpcode::ToL1Message => {
-                vec![Operand::RegOnly, Operand::RegOnly]
-            }
-            LogOpcode::StorageRead => vec![Operand::RegOnly],
-            LogOpcode::PrecompileCall => vec![Operand::RegOnly],
-        }
-    }
-    fn output_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        match self {
-            LogOpcode::StorageWrite | LogOpcode::Event | LogOpcode::ToL1Message => vec![],
-            LogOpcode::StorageRead => vec![Operand::RegOnly],
-            LogOpcode::PrecompileCall => vec![Operand::RegOnly],
-        }
-    }
+    opcode_props_from_isa_descriptions!();
     fn requires_kernel_mode(&self) -> bool {
         match self {
             LogOpcode::Event | LogOpcode::ToL1Message | LogOpcode::PrecompileCall => true,
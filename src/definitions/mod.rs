@@ -1,4 +1,9 @@
-use std::collections::HashSet;
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
 
 use crate::imm_mem_modifiers::*;
 
@@ -18,11 +23,13 @@ pub mod context;
 pub mod div;
 pub mod far_call;
 pub mod invalid_opcode;
+pub mod isa_diff;
 pub mod jump;
 pub mod log;
 pub mod mul;
 pub mod near_call;
 pub mod noop;
+pub mod opcode_spec;
 pub mod ptr;
 pub mod ret;
 pub mod shift;
@@ -37,11 +44,13 @@ pub use self::context::*;
 pub use self::div::*;
 pub use self::far_call::*;
 pub use self::invalid_opcode::*;
+pub use self::isa_diff::*;
 pub use self::jump::*;
 pub use self::log::*;
 pub use self::mul::*;
 pub use self::near_call::*;
 pub use self::noop::*;
+pub use self::opcode_spec::*;
 pub use self::ptr::*;
 pub use self::ret::*;
 pub use self::shift::*;
@@ -117,6 +126,7 @@ pub fn all_opcode_prototypes() -> [Opcode; NUM_OPCODES] {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpcodeVariant {
     pub opcode: Opcode,
     pub src0_operand_type: Operand,
@@ -133,6 +143,18 @@ impl OpcodeVariant {
         self.opcode.ergs_price()
     }
 
+    pub fn ergs_price_with(&self, schedule: &PricingSchedule) -> u32 {
+        self.opcode.ergs_price_with(schedule)
+    }
+
+    pub fn price_breakdown(&self) -> PriceBreakdown {
+        self.opcode.price_breakdown()
+    }
+
+    pub fn price_breakdown_with(&self, schedule: &PricingSchedule) -> PriceBreakdown {
+        self.opcode.price_breakdown_with(schedule)
+    }
+
     pub const fn swap_operands(&self) -> bool {
         match self.opcode {
             Opcode::Sub(_) | Opcode::Div(_) | Opcode::Shift(_) => {
@@ -162,7 +184,7 @@ impl OpcodeVariant {
     }
 }
 
-impl std::default::Default for OpcodeVariant {
+impl core::default::Default for OpcodeVariant {
     fn default() -> Self {
         OpcodeVariant {
             opcode: Opcode::Nop(NopOpcode),
@@ -173,8 +195,8 @@ impl std::default::Default for OpcodeVariant {
     }
 }
 
-impl std::fmt::Display for OpcodeVariant {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for OpcodeVariant {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "Opcode and variant: {:?}", self.opcode)?;
         writeln!(f, "Src0 operand type: {:?}", self.src0_operand_type)?;
         writeln!(f, "Dst0 operand type: {:?}", self.dst0_operand_type)?;
@@ -196,7 +218,7 @@ pub fn synthesize_opcode_decoding_tables(
     // - replace invalid opcode with Ret::Panic
 
     let all_opcodes = all_opcodes();
-    let mut checker = std::collections::HashSet::new();
+    let mut checker = BTreeSet::new();
     for (i, opcode) in all_opcodes.iter().enumerate() {
         let is_new = checker.insert(opcode.name());
         if !is_new {
@@ -244,7 +266,7 @@ pub fn synthesize_opcode_decoding_tables_legacy(
     let all_opcodes = all_opcodes();
     let all_prototypes = all_opcode_prototypes();
 
-    let mut checker = std::collections::HashSet::new();
+    let mut checker = BTreeSet::new();
     for (i, opcode) in all_opcodes.iter().enumerate() {
         let is_new = checker.insert(opcode.name());
         if !is_new {
@@ -439,7 +461,7 @@ fn all_variants_in_version(version: ISAVersion) -> Vec<OpcodeVariant> {
     let all_opcodes = all_opcodes();
     let all_prototypes = all_opcode_prototypes();
 
-    let mut checker = std::collections::HashSet::new();
+    let mut checker = BTreeSet::new();
     for (i, opcode) in all_opcodes.iter().enumerate() {
         let is_new = checker.insert(opcode.name());
         if !is_new {
@@ -724,7 +746,7 @@ pub fn synthesize_bit_decomposition_table(
 pub(crate) fn compute_encoding_density(for_version: ISAVersion) -> usize {
     let all_opcodes = all_opcodes();
 
-    let mut checker = std::collections::HashSet::new();
+    let mut checker = BTreeSet::new();
     for (i, opcode) in all_opcodes.iter().enumerate() {
         let is_new = checker.insert(opcode.name());
         if !is_new {
@@ -849,7 +871,7 @@ fn semantically_equal(a: &OpcodeVariant, b: &OpcodeVariant) -> bool {
 
 fn find_new_opcodes(old: &mut [OpcodeVariant], new: &[OpcodeVariant]) -> Vec<OpcodeVariant> {
     // sanity check that we always expand
-    let tmp: HashSet<OpcodeVariant> = HashSet::from_iter(new.iter().copied());
+    let tmp: BTreeSet<OpcodeVariant> = BTreeSet::from_iter(new.iter().copied());
     for el in old.iter() {
         if tmp.contains(&el) == false {
             // we may have semantic equivalents instead
@@ -1038,4 +1060,21 @@ mod test {
 
         dbg!(&difference);
     }
+
+    #[test]
+    fn future_versions_inherit_descriptions() {
+        let next_version = ISAVersion(LATEST_ISA_VERSION.0 + 1);
+        assert_eq!(
+            compute_encoding_density(next_version),
+            compute_encoding_density(LATEST_ISA_VERSION)
+        );
+        assert_eq!(
+            all_variants_in_version(next_version),
+            all_variants_in_version(LATEST_ISA_VERSION)
+        );
+        assert_eq!(
+            max_num_variants_for_version(next_version),
+            max_num_variants_for_version(LATEST_ISA_VERSION)
+        );
+    }
 }
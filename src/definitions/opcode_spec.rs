@@ -0,0 +1,292 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FlagSpec {
+    /// Index in `OpcodeVariant::flags`
+    pub index: usize,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImmediateSpec {
+    /// 0 for `imm_0`, 1 for `imm_1`
+    pub index: usize,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OperandSpec {
+    pub name: &'static str,
+    pub operand: Operand,
+}
+
+/// Human readable description of the opcode in some ISA version
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OpcodeSpec {
+    pub opcode: Opcode,
+    pub version: ISAVersion,
+    pub flags: Vec<FlagSpec>,
+    pub immediates: Vec<ImmediateSpec>,
+    /// `src0` and then `src1`
+    pub inputs: Vec<OperandSpec>,
+    /// `dst0` and then `dst1`
+    pub outputs: Vec<OperandSpec>,
+}
+
+impl OpcodeSpec {
+    pub fn flag(&self, name: &str) -> Option<&FlagSpec> {
+        self.flags.iter().find(|el| el.name == name)
+    }
+
+    pub fn uses_immediate(&self, index: usize) -> bool {
+        self.immediates.iter().any(|el| el.index == index)
+    }
+}
+
+const SET_FLAGS: FlagSpec = FlagSpec {
+    index: SET_FLAGS_FLAG_IDX,
+    name: "set_flags",
+    description: "update the lt, eq and gt flags from the result",
+};
+
+const fn swap_operands(index: usize) -> FlagSpec {
+    FlagSpec {
+        index,
+        name: "swap",
+        description: "swap src0 and src1 before applying the operation",
+    }
+}
+
+const FIRST_MESSAGE: FlagSpec = FlagSpec {
+    index: FIRST_MESSAGE_FLAG_IDX,
+    name: "first",
+    description: "marks the first query of a message that spans several queries",
+};
+
+fn flags_for_opcode(opcode: &Opcode) -> Vec<FlagSpec> {
+    match opcode {
+        Opcode::Add(_) | Opcode::Mul(_) | Opcode::Binop(_) => vec![SET_FLAGS],
+        Opcode::Sub(_) | Opcode::Div(_) | Opcode::Shift(_) => vec![
+            SET_FLAGS,
+            swap_operands(SWAP_OPERANDS_FLAG_IDX_FOR_ARITH_OPCODES),
+        ],
+        Opcode::Ptr(_) => vec![swap_operands(SWAP_OPERANDS_FLAG_IDX_FOR_PTR_OPCODE)],
+        Opcode::FarCall(_) => vec![
+            FlagSpec {
+                index: FAR_CALL_STATIC_FLAG_IDX,
+                name: "static",
+                description: "callee runs in the static context and can not modify the state",
+            },
+            FlagSpec {
+                index: FAR_CALL_SHARD_FLAG_IDX,
+                name: "shard",
+                description: "callee shard is taken from the far call ABI",
+            },
+        ],
+        Opcode::Ret(_) => vec![FlagSpec {
+            index: RET_TO_LABEL_BIT_IDX,
+            name: "to_label",
+            description: "continue at the label from imm_0 instead of the return address",
+        }],
+        Opcode::Log(LogOpcode::ToL1Message) | Opcode::Log(LogOpcode::Event) => {
+            vec![FIRST_MESSAGE]
+        }
+        Opcode::UMA(_) => vec![FlagSpec {
+            index: UMA_INCREMENT_FLAG_IDX,
+            name: "inc",
+            description: "also output the offset incremented by 32",
+        }],
+        _ => vec![],
+    }
+}
+
+fn immediates_for_opcode(opcode: &Opcode, version: ISAVersion) -> Vec<ImmediateSpec> {
+    let specific = match opcode {
+        Opcode::Jump(_) => vec![ImmediateSpec {
+            index: 0,
+            name: "jump_target",
+            description: "destination if src0 is an immediate, otherwise offset for src0",
+        }],
+        Opcode::NearCall(_) => vec![
+            ImmediateSpec {
+                index: 0,
+                name: "call_target",
+                description: "address of the callee",
+            },
+            ImmediateSpec {
+                index: 1,
+                name: "exception_handler",
+                description: "address to continue at if the callee panics or reverts",
+            },
+        ],
+        Opcode::FarCall(_) => vec![ImmediateSpec {
+            index: 0,
+            name: "exception_handler",
+            description: "address to continue at if the callee panics or reverts",
+        }],
+        Opcode::Ret(_) => vec![ImmediateSpec {
+            index: 0,
+            name: "label",
+            description: "address to continue at if the `to_label` flag is set",
+        }],
+        _ => vec![],
+    };
+    if !specific.is_empty() {
+        return specific;
+    }
+
+    let mut result = vec![];
+    match opcode.input_operands(version).first() {
+        Some(Operand::Full(_)) => result.push(ImmediateSpec {
+            index: 0,
+            name: "src0_imm",
+            description:
+                "immediate value of src0, or the stack or code page offset to read it from",
+        }),
+        Some(Operand::RegOrImm(_)) => result.push(ImmediateSpec {
+            index: 0,
+            name: "heap_offset",
+            description: "immediate offset to use instead of src0",
+        }),
+        _ => {}
+    }
+    if let Some(Operand::Full(_)) = opcode.output_operands(version).first() {
+        result.push(ImmediateSpec {
+            index: 1,
+            name: "dst0_offset",
+            description: "stack offset to write dst0 to",
+        });
+    }
+
+    result
+}
+
+fn operand_roles(opcode: &Opcode) -> (&'static [&'static str], &'static [&'static str]) {
+    match opcode {
+        Opcode::Invalid(_) => (&[], &[]),
+        Opcode::Nop(_) => (&["ignored"], &["ignored"]),
+        Opcode::Add(_) | Opcode::Sub(_) | Opcode::Binop(_) => (&["lhs", "rhs"], &["result"]),
+        Opcode::Mul(_) => (&["lhs", "rhs"], &["low", "high"]),
+        Opcode::Div(_) => (&["dividend", "divisor"], &["quotient", "remainder"]),
+        Opcode::Shift(_) => (&["value", "shift"], &["result"]),
+        Opcode::Ptr(PtrOpcode::Add) | Opcode::Ptr(PtrOpcode::Sub) => {
+            (&["fat_pointer", "offset_delta"], &["fat_pointer"])
+        }
+        Opcode::Ptr(PtrOpcode::Pack) => (&["fat_pointer", "high_bits"], &["fat_pointer"]),
+        Opcode::Ptr(PtrOpcode::Shrink) => (&["fat_pointer", "length_delta"], &["fat_pointer"]),
+        Opcode::Jump(_) => (&["destination"], &[]),
+        Opcode::Context(ContextOpcode::SetContextU128) => (&["value"], &[]),
+        Opcode::Context(ContextOpcode::SetErgsPerPubdataByte) => (&["ergs_per_pubdata_byte"], &[]),
+        Opcode::Context(ContextOpcode::IncrementTxNumber) => (&[], &[]),
+        Opcode::Context(_) => (&[], &["value"]),
+        Opcode::NearCall(_) => (&["ergs_to_pass"], &[]),
+        Opcode::FarCall(_) => (&["abi", "destination"], &[]),
+        Opcode::Ret(RetOpcode::Panic) => (&[], &[]),
+        Opcode::Ret(_) => (&["abi"], &[]),
+        Opcode::Log(LogOpcode::StorageRead) => (&["key"], &["value"]),
+        Opcode::Log(LogOpcode::StorageWrite)
+        | Opcode::Log(LogOpcode::ToL1Message)
+        | Opcode::Log(LogOpcode::Event) => (&["key", "value"], &[]),
+        Opcode::Log(LogOpcode::PrecompileCall) => (&["abi"], &["success"]),
+        Opcode::UMA(UMAOpcode::HeapRead) | Opcode::UMA(UMAOpcode::AuxHeapRead) => {
+            (&["offset"], &["value", "incremented_offset"])
+        }
+        Opcode::UMA(UMAOpcode::HeapWrite) | Opcode::UMA(UMAOpcode::AuxHeapWrite) => {
+            (&["offset", "value"], &["incremented_offset"])
+        }
+        Opcode::UMA(UMAOpcode::FatPointerRead) => {
+            (&["fat_pointer"], &["value", "incremented_fat_pointer"])
+        }
+    }
+}
+
+// every operand is named, that is checked by the tests for all opcodes and versions
+fn operand_specs(roles: &[&'static str], operands: Vec<Operand>) -> Vec<OperandSpec> {
+    roles
+        .iter()
+        .zip(operands)
+        .map(|(name, operand)| OperandSpec { name, operand })
+        .collect()
+}
+
+impl Opcode {
+    /// Returns `None` if the opcode is not available in this version
+    pub fn spec(&self, version: ISAVersion) -> Option<OpcodeSpec> {
+        if !self.is_available_for_version(version) {
+            return None;
+        }
+
+        let (input_roles, output_roles) = operand_roles(self);
+
+        Some(OpcodeSpec {
+            opcode: *self,
+            version,
+            flags: flags_for_opcode(self),
+            immediates: immediates_for_opcode(self, version),
+            inputs: operand_specs(input_roles, self.input_operands(version)),
+            outputs: operand_specs(output_roles, self.output_operands(version)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn specs_match_encoding() {
+        for version in ALL_ISA_VERSIONS.iter() {
+            let all_prototypes = all_opcode_prototypes();
+            for (idx, opcode) in all_opcodes().iter().enumerate() {
+                for variant_data in opcode.variants_data(*version) {
+                    let opcode = all_prototypes[idx]
+                        .materialize_subvariant_from_prototype(variant_data.variant_idx, version);
+                    let spec = opcode.spec(*version).unwrap();
+                    assert_eq!(spec.flags.len(), variant_data.num_non_exclusive_flags);
+                    for (idx, flag) in spec.flags.iter().enumerate() {
+                        assert_eq!(flag.index, idx, "flags of {:?} are not sorted", opcode);
+                    }
+                    assert_eq!(
+                        spec.immediates.len(),
+                        variant_data.num_used_immediates,
+                        "immediates of {:?} in version {}",
+                        opcode,
+                        version.0
+                    );
+                    assert_eq!(
+                        spec.inputs.len(),
+                        opcode.input_operands(*version).len(),
+                        "inputs of {:?} are not named",
+                        opcode
+                    );
+                    assert_eq!(
+                        spec.outputs.len(),
+                        opcode.output_operands(*version).len(),
+                        "outputs of {:?} are not named",
+                        opcode
+                    );
+                }
+            }
+        }
+
+        let spec = Opcode::Sub(SubOpcode::Sub).spec(ISAVersion(0)).unwrap();
+        assert_eq!(spec.flag("swap").unwrap().index, 1);
+        assert!(spec.uses_immediate(0) && spec.uses_immediate(1));
+
+        let spec = Opcode::UMA(UMAOpcode::HeapWrite)
+            .spec(ISAVersion(1))
+            .unwrap();
+        assert_eq!(spec.immediates[0].name, "heap_offset");
+        assert_eq!(spec.inputs[1].name, "value");
+        assert!(Opcode::UMA(UMAOpcode::HeapWrite)
+            .spec(ISAVersion(0))
+            .unwrap()
+            .immediates
+            .is_empty());
+    }
+}
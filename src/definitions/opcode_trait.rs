@@ -1,6 +1,10 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
 use super::*;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operand {
     RegOnly,
     RegOrImm(RegOrImmFlags),
@@ -36,8 +40,22 @@ impl ISAVersion {
     }
 }
 
-pub const NUM_ISA_VERSIONS: usize = 2;
-pub const ALL_ISA_VERSIONS: [ISAVersion; NUM_ISA_VERSIONS] = [ISAVersion(0), ISAVersion(1)];
+// adding a new version only requires to bump it and describe the opcodes that change
+// in `ISA_DESCRIPTIONS` of the corresponding opcode, all others are inherited
+pub const LATEST_ISA_VERSION: ISAVersion = ISAVersion(1);
+pub const NUM_ISA_VERSIONS: usize = LATEST_ISA_VERSION.as_index() + 1;
+pub const ALL_ISA_VERSIONS: [ISAVersion; NUM_ISA_VERSIONS] = all_isa_versions();
+
+const fn all_isa_versions() -> [ISAVersion; NUM_ISA_VERSIONS] {
+    let mut result = [ISAVersion(0); NUM_ISA_VERSIONS];
+    let mut i = 0;
+    while i < NUM_ISA_VERSIONS {
+        result[i] = ISAVersion(i as u8);
+        i += 1;
+    }
+
+    result
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct OpcodeVariantData {
@@ -85,12 +103,172 @@ pub trait OpcodeProps: 'static + Send + Sync {
     }
 }
 
-pub trait OpcodeVariantProps: Sized + 'static + Send + Sync {
-    fn all_variants() -> Vec<Self>;
-    fn minimal_version(&self) -> ISAVersion;
-    fn max_variant_idx_for_version(version: ISAVersion) -> usize;
+/// Encoding of the variants that share the same flags and addressing modes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VariantsDescription {
+    pub variant_indices: &'static [usize],
+    pub num_non_exclusive_flags: usize,
+    pub num_used_immediates: usize,
+    pub input_operands: &'static [Operand],
+    pub output_operands: &'static [Operand],
+}
+
+/// Full set of the opcode variants that are encodable starting from `since`.
+/// Later versions inherit it until the next description
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OpcodeVersionDescription {
+    pub since: ISAVersion,
+    pub variants: &'static [VariantsDescription],
+}
+
+impl OpcodeVersionDescription {
+    pub fn find_variant(&self, variant_idx: usize) -> Option<&'static VariantsDescription> {
+        self.variants
+            .iter()
+            .find(|el| el.variant_indices.contains(&variant_idx))
+    }
+
+    pub fn variants_data(&self) -> Vec<OpcodeVariantData> {
+        let mut result = vec![];
+        for el in self.variants.iter() {
+            for variant_idx in el.variant_indices.iter() {
+                result.push(OpcodeVariantData {
+                    variant_idx: *variant_idx,
+                    num_non_exclusive_flags: el.num_non_exclusive_flags,
+                    num_used_immediates: el.num_used_immediates,
+                });
+            }
+        }
+        // encoding tables are filled in the order of variant indices
+        result.sort_by_key(|el| el.variant_idx);
+
+        result
+    }
+}
+
+/// Implemented by `opcode_variants!`. Variants are indexed in the order of declaration
+pub trait OpcodeVariantsEnum: Sized + Copy + 'static {
+    const ALL_VARIANTS: &'static [Self];
+    fn declaration_index(&self) -> usize;
+    /// `None` for the opcodes without sub-variants
+    fn variant_name(&self) -> Option<&'static str>;
+}
+
+// Declares the opcode variants enum (or a unit struct for opcodes without sub-variants)
+macro_rules! opcode_variants {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($variant:ident),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[repr(u8)]
+        $vis enum $name {
+            $($variant),+
+        }
+
+        impl $crate::definitions::OpcodeVariantsEnum for $name {
+            const ALL_VARIANTS: &'static [Self] = &[$($name::$variant),+];
+
+            fn declaration_index(&self) -> usize {
+                (*self as u8) as usize
+            }
+
+            fn variant_name(&self) -> Option<&'static str> {
+                match self {
+                    $($name::$variant => Some(stringify!($variant))),+
+                }
+            }
+        }
+    };
+    ($(#[$meta:meta])* $vis:vis struct $name:ident;) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        $vis struct $name;
+
+        impl $crate::definitions::OpcodeVariantsEnum for $name {
+            const ALL_VARIANTS: &'static [Self] = &[$name];
+
+            fn declaration_index(&self) -> usize {
+                0
+            }
+
+            fn variant_name(&self) -> Option<&'static str> {
+                None
+            }
+        }
+    };
+}
+
+pub(crate) use opcode_variants;
+
+// Implements version dependent methods of `OpcodeProps` from `OpcodeVariantProps::ISA_DESCRIPTIONS`,
+// should be used inside of the `impl OpcodeProps` block
+macro_rules! opcode_props_from_isa_descriptions {
+    () => {
+        fn variants_data(&self, version: ISAVersion) -> alloc::vec::Vec<OpcodeVariantData> {
+            <Self as OpcodeVariantProps>::variants_data_for_version(version)
+        }
+        fn max_variant_idx(&self, version: ISAVersion) -> usize {
+            <Self as OpcodeVariantProps>::max_variant_idx_for_version(version)
+        }
+        fn input_operands(&self, version: ISAVersion) -> alloc::vec::Vec<Operand> {
+            OpcodeVariantProps::input_operands_for_version(self, version)
+        }
+        fn output_operands(&self, version: ISAVersion) -> alloc::vec::Vec<Operand> {
+            OpcodeVariantProps::output_operands_for_version(self, version)
+        }
+    };
+}
+
+pub(crate) use opcode_props_from_isa_descriptions;
+
+pub trait OpcodeVariantProps: OpcodeVariantsEnum + Send + Sync {
+    /// Sorted by the version
+    const ISA_DESCRIPTIONS: &'static [OpcodeVersionDescription];
+    fn all_variants() -> Vec<Self> {
+        Self::ALL_VARIANTS.to_vec()
+    }
+    /// Returns `None` if the opcode doesn't exist yet in this version
+    fn description_for_version(version: ISAVersion) -> Option<&'static OpcodeVersionDescription> {
+        Self::ISA_DESCRIPTIONS
+            .iter()
+            .rev()
+            .find(|el| el.since <= version)
+    }
+    fn variant_description(&self, version: ISAVersion) -> Option<&'static VariantsDescription> {
+        Self::description_for_version(version)?.find_variant(self.variant_index())
+    }
+    fn variants_data_for_version(version: ISAVersion) -> Vec<OpcodeVariantData> {
+        Self::description_for_version(version)
+            .map(|el| el.variants_data())
+            .unwrap_or_default()
+    }
+    fn input_operands_for_version(&self, version: ISAVersion) -> Vec<Operand> {
+        self.variant_description(version)
+            .map(|el| el.input_operands.to_vec())
+            .unwrap_or_default()
+    }
+    fn output_operands_for_version(&self, version: ISAVersion) -> Vec<Operand> {
+        self.variant_description(version)
+            .map(|el| el.output_operands.to_vec())
+            .unwrap_or_default()
+    }
+    fn minimal_version(&self) -> ISAVersion {
+        Self::ISA_DESCRIPTIONS
+            .iter()
+            .find(|el| el.find_variant(self.variant_index()).is_some())
+            .map(|el| el.since)
+            .expect("every variant must be described in some ISA version")
+    }
+    fn max_variant_idx_for_version(version: ISAVersion) -> usize {
+        Self::variants_data_for_version(version)
+            .into_iter()
+            .map(|el| el.variant_idx)
+            .max()
+            .unwrap_or(0)
+    }
     fn is_available_for_version(&self, version: ISAVersion) -> bool {
-        self.minimal_version() >= version
+        self.minimal_version() <= version
     }
     fn is_added_in_version(&self, version: ISAVersion) -> bool {
         self.minimal_version() == version
@@ -107,27 +285,149 @@ pub trait OpcodeVariantProps: Sized + 'static + Send + Sync {
             .filter(|el| el.is_added_in_version(version))
             .collect()
     }
-    fn variant_index(&self) -> usize;
-    fn from_variant_index_for_version(index: usize, version: &ISAVersion) -> Option<Self>;
-    fn ergs_price(&self) -> u32;
+    fn variant_index(&self) -> usize {
+        self.declaration_index()
+    }
+    /// Returns `None` if there is no such variant or it's not introduced yet in this version
+    fn from_variant_index_for_version(index: usize, version: &ISAVersion) -> Option<Self> {
+        Self::all_variants()
+            .into_iter()
+            .find(|el| el.variant_index() == index && el.is_available_for_version(*version))
+    }
+    fn price_breakdown_with(&self, schedule: &PricingSchedule) -> PriceBreakdown;
+    fn price_breakdown(&self) -> PriceBreakdown {
+        self.price_breakdown_with(&DEFAULT_PRICING_SCHEDULE)
+    }
+    fn ergs_price_with(&self, schedule: &PricingSchedule) -> u32 {
+        self.price_breakdown_with(schedule).total()
+    }
+    fn ergs_price(&self) -> u32 {
+        self.ergs_price_with(&DEFAULT_PRICING_SCHEDULE)
+    }
 }
 
-use std::ops::RangeInclusive;
+#[cfg(test)]
+mod test {
+    use super::*;
 
-pub(crate) fn full_variants_product(
-    variants_range: RangeInclusive<usize>,
-    num_non_exclusive_flags: usize,
-    num_immedates: usize,
-) -> Vec<OpcodeVariantData> {
-    let mut result = Vec::with_capacity(variants_range.clone().count());
-    for idx in variants_range {
-        let descr = OpcodeVariantData {
-            variant_idx: idx,
-            num_non_exclusive_flags: num_non_exclusive_flags,
-            num_used_immediates: num_immedates,
-        };
-        result.push(descr);
+    opcode_variants! {
+        enum TestOpcode {
+            Old,
+            New,
+        }
     }
 
-    result
+    impl OpcodeVariantProps for TestOpcode {
+        const ISA_DESCRIPTIONS: &'static [OpcodeVersionDescription] = &[
+            OpcodeVersionDescription {
+                since: ISAVersion(0),
+                variants: &[VariantsDescription {
+                    variant_indices: &[TestOpcode::Old as usize],
+                    num_non_exclusive_flags: 0,
+                    num_used_immediates: 0,
+                    input_operands: &[],
+                    output_operands: &[],
+                }],
+            },
+            OpcodeVersionDescription {
+                since: ISAVersion(1),
+                variants: &[VariantsDescription {
+                    variant_indices: &[TestOpcode::Old as usize, TestOpcode::New as usize],
+                    num_non_exclusive_flags: 0,
+                    num_used_immediates: 0,
+                    input_operands: &[],
+                    output_operands: &[],
+                }],
+            },
+        ];
+
+        fn price_breakdown_with(&self, _schedule: &PricingSchedule) -> PriceBreakdown {
+            PriceBreakdown::default()
+        }
+    }
+
+    fn check_availability<T: OpcodeVariantProps + PartialEq + core::fmt::Debug>() {
+        for version in ALL_ISA_VERSIONS.iter() {
+            for variant in T::all_variants() {
+                let available = variant.minimal_version() <= *version;
+                assert_eq!(variant.is_available_for_version(*version), available);
+                assert_eq!(variant.variant_description(*version).is_some(), available);
+                assert_eq!(
+                    variant.is_added_in_version(*version),
+                    variant.minimal_version() == *version
+                );
+
+                let materialized =
+                    T::from_variant_index_for_version(variant.variant_index(), version);
+                if available {
+                    assert_eq!(materialized, Some(variant));
+                } else {
+                    assert_eq!(materialized, None);
+                }
+            }
+
+            let expected: Vec<T> = T::all_variants()
+                .into_iter()
+                .filter(|el| el.minimal_version() <= *version)
+                .collect();
+            assert_eq!(T::variants_for_version(*version), expected);
+            assert_eq!(
+                T::from_variant_index_for_version(T::all_variants().len(), version),
+                None
+            );
+        }
+
+        let mut added = vec![];
+        for version in ALL_ISA_VERSIONS.iter() {
+            added.extend(T::variants_added_in_version(*version));
+        }
+        assert_eq!(added.len(), T::all_variants().len());
+    }
+
+    #[test]
+    fn availability_for_all_opcodes() {
+        check_availability::<InvalidOpcode>();
+        check_availability::<NopOpcode>();
+        check_availability::<AddOpcode>();
+        check_availability::<SubOpcode>();
+        check_availability::<MulOpcode>();
+        check_availability::<DivOpcode>();
+        check_availability::<JumpOpcode>();
+        check_availability::<ContextOpcode>();
+        check_availability::<ShiftOpcode>();
+        check_availability::<BinopOpcode>();
+        check_availability::<PtrOpcode>();
+        check_availability::<NearCallOpcode>();
+        check_availability::<LogOpcode>();
+        check_availability::<FarCallOpcode>();
+        check_availability::<RetOpcode>();
+        check_availability::<UMAOpcode>();
+        check_availability::<TestOpcode>();
+    }
+
+    #[test]
+    fn variant_added_in_later_version() {
+        assert_eq!(TestOpcode::New.minimal_version(), ISAVersion(1));
+        assert!(!TestOpcode::New.is_available_for_version(ISAVersion(0)));
+        assert!(TestOpcode::New.is_available_for_version(ISAVersion(1)));
+        assert!(TestOpcode::New.is_available_for_version(ISAVersion(2)));
+        assert!(TestOpcode::Old.is_available_for_version(ISAVersion(1)));
+
+        assert_eq!(
+            TestOpcode::variants_for_version(ISAVersion(0)),
+            vec![TestOpcode::Old]
+        );
+        assert_eq!(
+            TestOpcode::variants_added_in_version(ISAVersion(1)),
+            vec![TestOpcode::New]
+        );
+        assert_eq!(
+            TestOpcode::from_variant_index_for_version(1, &ISAVersion(0)),
+            None
+        );
+        assert_eq!(
+            TestOpcode::from_variant_index_for_version(1, &ISAVersion(1)),
+            Some(TestOpcode::New)
+        );
+    }
 }
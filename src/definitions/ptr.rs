@@ -1,51 +1,39 @@
 use super::*;
 use ethereum_types::U256;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[repr(u8)]
-pub enum PtrOpcode {
-    Add = 0,
-    Sub,
-    Pack,
-    Shrink,
+opcode_variants! {
+    pub enum PtrOpcode {
+        Add,
+        Sub,
+        Pack,
+        Shrink,
+    }
 }
 
 pub const MAX_OFFSET_FOR_ADD_SUB: U256 = U256([1u64 << 32, 0, 0, 0]);
 
 impl OpcodeVariantProps for PtrOpcode {
-    fn all_variants() -> Vec<Self> {
-        vec![
-            PtrOpcode::Add,
-            PtrOpcode::Sub,
-            PtrOpcode::Pack,
-            PtrOpcode::Shrink,
-        ]
-    }
-
-    fn max_variant_idx_for_version(_version: ISAVersion) -> usize {
-        PtrOpcode::Shrink.variant_index()
-    }
-
-    fn minimal_version(&self) -> ISAVersion {
-        ALL_ISA_VERSIONS[0]
-    }
-
-    fn variant_index(&self) -> usize {
-        (*self as u8) as usize
-    }
-
-    fn from_variant_index_for_version(index: usize, _version: &ISAVersion) -> Option<Self> {
-        match index {
-            i if i == PtrOpcode::Add.variant_index() => Some(PtrOpcode::Add),
-            i if i == PtrOpcode::Sub.variant_index() => Some(PtrOpcode::Sub),
-            i if i == PtrOpcode::Pack.variant_index() => Some(PtrOpcode::Pack),
-            i if i == PtrOpcode::Shrink.variant_index() => Some(PtrOpcode::Shrink),
-            _ => None,
-        }
-    }
-
-    fn ergs_price(&self) -> u32 {
-        RICH_ADDRESSING_OPCODE_ERGS
+    const ISA_DESCRIPTIONS: &'static [OpcodeVersionDescription] = &[OpcodeVersionDescription {
+        since: ISAVersion(0),
+        variants: &[VariantsDescription {
+            variant_indices: &[
+                PtrOpcode::Add as usize,
+                PtrOpcode::Sub as usize,
+                PtrOpcode::Pack as usize,
+                PtrOpcode::Shrink as usize,
+            ],
+            num_non_exclusive_flags: 1,
+            num_used_immediates: 2,
+            input_operands: &[
+                Operand::Full(ImmMemHandlerFlags::UseRegOnly),
+                Operand::RegOnly,
+            ],
+            output_operands: &[Operand::Full(ImmMemHandlerFlags::UseRegOnly)],
+        }],
+    }];
+
+    fn price_breakdown_with(&self, schedule: &PricingSchedule) -> PriceBreakdown {
+        schedule.rich_addressing_opcode_breakdown()
     }
 }
 
@@ -53,29 +41,7 @@ impl OpcodeProps for PtrOpcode {
     fn name(&self) -> &'static str {
         "Ptr opcode"
     }
-    fn variants_data(&self, version: ISAVersion) -> Vec<OpcodeVariantData> {
-        match version {
-            ISAVersion(0) => {
-                full_variants_product(0..=Self::max_variant_idx_for_version(version), 1, 2)
-            }
-            ISAVersion(1) => {
-                full_variants_product(0..=Self::max_variant_idx_for_version(version), 1, 2)
-            }
-            _ => unimplemented!(),
-        }
-    }
-    fn max_variant_idx(&self, _version: ISAVersion) -> usize {
-        PtrOpcode::Pack.variant_index()
-    }
-    fn input_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        vec![
-            Operand::Full(ImmMemHandlerFlags::UseRegOnly),
-            Operand::RegOnly,
-        ]
-    }
-    fn output_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        vec![Operand::Full(ImmMemHandlerFlags::UseRegOnly)]
-    }
+    opcode_props_from_isa_descriptions!();
     fn requires_kernel_mode(&self) -> bool {
         false
     }
@@ -1,48 +1,36 @@
 use super::*;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[repr(u8)]
-pub enum ShiftOpcode {
-    Shl = 0,
-    Shr,
-    Rol,
-    Ror,
+opcode_variants! {
+    pub enum ShiftOpcode {
+        Shl,
+        Shr,
+        Rol,
+        Ror,
+    }
 }
 
 impl OpcodeVariantProps for ShiftOpcode {
-    fn all_variants() -> Vec<Self> {
-        vec![
-            ShiftOpcode::Shl,
-            ShiftOpcode::Shr,
-            ShiftOpcode::Rol,
-            ShiftOpcode::Ror,
-        ]
-    }
-
-    fn max_variant_idx_for_version(_version: ISAVersion) -> usize {
-        ShiftOpcode::Ror.variant_index()
-    }
-
-    fn minimal_version(&self) -> ISAVersion {
-        ALL_ISA_VERSIONS[0]
-    }
-
-    fn variant_index(&self) -> usize {
-        (*self as u8) as usize
-    }
-
-    fn from_variant_index_for_version(index: usize, _version: &ISAVersion) -> Option<Self> {
-        match index {
-            i if i == ShiftOpcode::Shl.variant_index() => Some(ShiftOpcode::Shl),
-            i if i == ShiftOpcode::Shr.variant_index() => Some(ShiftOpcode::Shr),
-            i if i == ShiftOpcode::Rol.variant_index() => Some(ShiftOpcode::Rol),
-            i if i == ShiftOpcode::Ror.variant_index() => Some(ShiftOpcode::Ror),
-            _ => None,
-        }
-    }
-
-    fn ergs_price(&self) -> u32 {
-        RICH_ADDRESSING_OPCODE_ERGS
+    const ISA_DESCRIPTIONS: &'static [OpcodeVersionDescription] = &[OpcodeVersionDescription {
+        since: ISAVersion(0),
+        variants: &[VariantsDescription {
+            variant_indices: &[
+                ShiftOpcode::Shl as usize,
+                ShiftOpcode::Shr as usize,
+                ShiftOpcode::Rol as usize,
+                ShiftOpcode::Ror as usize,
+            ],
+            num_non_exclusive_flags: 2,
+            num_used_immediates: 2,
+            input_operands: &[
+                Operand::Full(ImmMemHandlerFlags::UseRegOnly),
+                Operand::RegOnly,
+            ],
+            output_operands: &[Operand::Full(ImmMemHandlerFlags::UseRegOnly)],
+        }],
+    }];
+
+    fn price_breakdown_with(&self, schedule: &PricingSchedule) -> PriceBreakdown {
+        schedule.rich_addressing_opcode_breakdown()
     }
 }
 
@@ -50,29 +38,7 @@ impl OpcodeProps for ShiftOpcode {
     fn name(&self) -> &'static str {
         "Shift opcode"
     }
-    fn variants_data(&self, version: ISAVersion) -> Vec<OpcodeVariantData> {
-        match version {
-            ISAVersion(0) => {
-                full_variants_product(0..=Self::max_variant_idx_for_version(version), 2, 2)
-            }
-            ISAVersion(1) => {
-                full_variants_product(0..=Self::max_variant_idx_for_version(version), 2, 2)
-            }
-            _ => unimplemented!(),
-        }
-    }
-    fn max_variant_idx(&self, _version: ISAVersion) -> usize {
-        ShiftOpcode::Ror.variant_index()
-    }
-    fn input_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        vec![
-            Operand::Full(ImmMemHandlerFlags::UseRegOnly),
-            Operand::RegOnly,
-        ]
-    }
-    fn output_operands(&self, _version: ISAVersion) -> Vec<Operand> {
-        vec![Operand::Full(ImmMemHandlerFlags::UseRegOnly)]
-    }
+    opcode_props_from_isa_descriptions!();
     fn requires_kernel_mode(&self) -> bool {
         false
     }
@@ -1,8 +1,8 @@
 pub trait VersionedHashDef:
-    Send + Sync + Sized + Clone + Copy + PartialEq + Eq + std::hash::Hash
+    Send + Sync + Sized + Clone + Copy + PartialEq + Eq + core::hash::Hash
 {
     const VERSION_BYTE: u8;
-    type StorageLayout: Send + Sync + Sized + Clone + Copy + PartialEq + Eq + std::hash::Hash;
+    type StorageLayout: Send + Sync + Sized + Clone + Copy + PartialEq + Eq + core::hash::Hash;
     fn serialize(storage: Self::StorageLayout) -> Option<[u8; 32]>;
     fn serialize_to_stored(storage: Self::StorageLayout) -> Option<[u8; 32]>;
     fn try_deserialize(input: [u8; 32]) -> Option<Self::StorageLayout>;
@@ -52,9 +52,11 @@ impl VersionedHashGeneric<ContractCodeSha256> {
 pub struct ContractCodeSha256;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ContractCodeSha256Storage {
     pub code_length_in_words: u16,
     pub extra_marker: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub partial_hash: [u8; 28],
 }
 
@@ -63,6 +65,70 @@ impl ContractCodeSha256 {
     pub const YET_CONSTRUCTED_MARKER: u8 = 1;
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConstructionState {
+    Constructed,
+    UnderConstruction,
+}
+
+impl ConstructionState {
+    pub const fn from_marker(marker: u8) -> Option<Self> {
+        match marker {
+            ContractCodeSha256::CODE_AT_REST_MARKER => Some(ConstructionState::Constructed),
+            ContractCodeSha256::YET_CONSTRUCTED_MARKER => {
+                Some(ConstructionState::UnderConstruction)
+            }
+            _ => None,
+        }
+    }
+
+    pub const fn marker(&self) -> u8 {
+        match self {
+            ConstructionState::Constructed => ContractCodeSha256::CODE_AT_REST_MARKER,
+            ConstructionState::UnderConstruction => ContractCodeSha256::YET_CONSTRUCTED_MARKER,
+        }
+    }
+}
+
+impl ContractCodeSha256Storage {
+    /// Returns `None` if the marker byte is not one of the known ones
+    pub const fn construction_state(&self) -> Option<ConstructionState> {
+        ConstructionState::from_marker(self.extra_marker)
+    }
+}
+
+impl VersionedHashGeneric<ContractCodeSha256> {
+    pub fn construction_state(&self) -> ConstructionState {
+        // marker is checked when the hash is created
+        self.data
+            .construction_state()
+            .expect("marker of the versioned hash is always known")
+    }
+
+    pub fn is_being_constructed(&self) -> bool {
+        self.construction_state() == ConstructionState::UnderConstruction
+    }
+
+    pub fn is_constructed(&self) -> bool {
+        self.construction_state() == ConstructionState::Constructed
+    }
+
+    pub fn with_construction_state(self, state: ConstructionState) -> Self {
+        let mut data = self.data;
+        data.extra_marker = state.marker();
+
+        Self { data }
+    }
+
+    pub fn mark_as_constructed(self) -> Self {
+        self.with_construction_state(ConstructionState::Constructed)
+    }
+
+    pub fn mark_as_under_construction(self) -> Self {
+        self.with_construction_state(ConstructionState::UnderConstruction)
+    }
+}
+
 impl VersionedHashDef for ContractCodeSha256 {
     const VERSION_BYTE: u8 = 0x01;
     type StorageLayout = ContractCodeSha256Storage;
@@ -90,6 +156,8 @@ impl VersionedHashDef for ContractCodeSha256 {
         }
 
         let extra_marker = input[1];
+        // unknown markers are rejected, so every hash is in a known construction state
+        ConstructionState::from_marker(extra_marker)?;
 
         let code_length_in_words = u16::from_be_bytes([input[2], input[3]]);
         let partial_hash: [u8; 28] = input[4..32].try_into().unwrap();
@@ -101,3 +169,86 @@ impl VersionedHashDef for ContractCodeSha256 {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_hash() -> VersionedHashGeneric<ContractCodeSha256> {
+        let mut digest = [0u8; 32];
+        for (idx, el) in digest.iter_mut().enumerate() {
+            *el = idx as u8;
+        }
+
+        VersionedHashGeneric::from_digest_and_preimage_num_words(digest, 0x0103)
+    }
+
+    #[test]
+    fn construction_state_round_trip() {
+        let hash = test_hash();
+        assert!(hash.is_constructed());
+        assert!(!hash.is_being_constructed());
+
+        for state in [
+            ConstructionState::Constructed,
+            ConstructionState::UnderConstruction,
+        ] {
+            let hash = hash.with_construction_state(state);
+            assert_eq!(hash.construction_state(), state);
+
+            let raw = hash.serialize().unwrap();
+            assert_eq!(raw[1], state.marker());
+            let parsed =
+                VersionedHashGeneric::<ContractCodeSha256>::try_create_from_raw(raw).unwrap();
+            assert_eq!(parsed, hash);
+            assert_eq!(parsed.construction_state(), state);
+
+            // stored form is always at rest
+            let stored = hash.serialize_to_stored().unwrap();
+            assert_eq!(stored[1], ContractCodeSha256::CODE_AT_REST_MARKER);
+        }
+    }
+
+    #[test]
+    fn transitions_keep_length_and_hash() {
+        let hash = test_hash();
+        let under_construction = hash.mark_as_under_construction();
+        assert!(under_construction.is_being_constructed());
+        assert_eq!(
+            under_construction.layout_ref().code_length_in_words,
+            hash.layout_ref().code_length_in_words
+        );
+        assert_eq!(
+            under_construction.layout_ref().partial_hash,
+            hash.layout_ref().partial_hash
+        );
+
+        let raw = hash.serialize().unwrap();
+        let raw_under_construction = under_construction.serialize().unwrap();
+        assert_eq!(raw[0], raw_under_construction[0]);
+        assert_eq!(raw[2..], raw_under_construction[2..]);
+
+        assert_eq!(under_construction.mark_as_constructed(), hash);
+    }
+
+    #[test]
+    fn unknown_markers_are_rejected() {
+        let mut raw = test_hash().serialize().unwrap();
+        for marker in 2..=u8::MAX {
+            raw[1] = marker;
+            assert!(VersionedHashGeneric::<ContractCodeSha256>::try_create_from_raw(raw).is_none());
+            assert_eq!(ConstructionState::from_marker(marker), None);
+        }
+
+        let storage = ContractCodeSha256Storage {
+            code_length_in_words: 1,
+            extra_marker: 7,
+            partial_hash: [0u8; 28],
+        };
+        assert_eq!(storage.construction_state(), None);
+
+        raw[1] = ContractCodeSha256::CODE_AT_REST_MARKER;
+        raw[0] = 0x02;
+        assert!(VersionedHashGeneric::<ContractCodeSha256>::try_create_from_raw(raw).is_none());
+    }
+}
@@ -0,0 +1,357 @@
+use ethereum_types::Address;
+
+use crate::decoding::EncodingModeProduction;
+use crate::system_params::{
+    ADDRESS_MSG_VALUE, MSG_VALUE_SIMULATOR_ADDITIVE_COST, MSG_VALUE_SIMULATOR_MIN_USED_ERGS,
+    VM_MAX_STACK_DEPTH,
+};
+use crate::utils::check_bytecode_length_for_mode;
+use crate::{
+    ContractCodeSha256, ContractCodeSha256Storage, FarCallABI, VersionedHashGeneric,
+    ERGS_PER_CODE_WORD_DECOMMITTMENT,
+};
+
+/// Caller can pass at most 63/64 of its remaining ergs
+pub const FAR_CALL_ERGS_RETAINED_FRACTION: u32 = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FarCallErgsParams {
+    /// Ergs of the caller after paying for the far call opcode itself,
+    /// that includes `CALL_LIKE_ERGS_COST` (see `Opcode::ergs_price`)
+    pub caller_remaining_ergs: u32,
+    /// Number of frames on the callstack before the call, the callee frame
+    /// can not be pushed if it reaches `VM_MAX_STACK_DEPTH`
+    pub stack_depth: u32,
+    /// As requested in `FarCallABI`
+    pub ergs_passed: u32,
+    pub callee_address: Address,
+    pub is_system_call: bool,
+    pub code_length_in_words: u16,
+    /// Code that was already decommitted in this batch is free to use
+    pub code_already_decommitted: bool,
+}
+
+impl FarCallErgsParams {
+    pub fn from_abi(
+        caller_remaining_ergs: u32,
+        stack_depth: u32,
+        abi: &FarCallABI,
+        callee_address: Address,
+        code_length_in_words: u16,
+        code_already_decommitted: bool,
+    ) -> Self {
+        Self {
+            caller_remaining_ergs,
+            stack_depth,
+            ergs_passed: abi.ergs_passed,
+            callee_address,
+            is_system_call: abi.to_system,
+            code_length_in_words,
+            code_already_decommitted,
+        }
+    }
+
+    pub fn from_abi_and_code_hash(
+        caller_remaining_ergs: u32,
+        stack_depth: u32,
+        abi: &FarCallABI,
+        callee_address: Address,
+        code_hash: &VersionedHashGeneric<ContractCodeSha256>,
+        code_already_decommitted: bool,
+    ) -> Self {
+        Self::from_abi(
+            caller_remaining_ergs,
+            stack_depth,
+            abi,
+            callee_address,
+            code_hash.layout_ref().code_length_in_words,
+            code_already_decommitted,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FarCallErgsSplit {
+    pub decommitment_cost: u32,
+    pub ergs_passed: u32,
+    pub ergs_retained: u32,
+    /// Given to the callee on top of the passed ergs, so the callee starts
+    /// with `ergs_passed + callee_stipend`
+    pub callee_stipend: u32,
+    /// Part of the stipend that is never returned to the caller, even if the callee
+    /// spends less. It's `MSG_VALUE_SIMULATOR_MIN_USED_ERGS` for the msg.value simulator
+    pub callee_stipend_min_used: u32,
+}
+
+impl FarCallErgsSplit {
+    pub const fn callee_ergs(&self) -> u32 {
+        self.ergs_passed.saturating_add(self.callee_stipend)
+    }
+
+    /// Most ergs that the caller can get back from the stipend when the callee returns
+    pub const fn max_stipend_refund(&self) -> u32 {
+        self.callee_stipend
+            .saturating_sub(self.callee_stipend_min_used)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FarCallErgsError {
+    StackDepthExceeded {
+        stack_depth: u32,
+    },
+    NotEnoughErgsForDecommitment {
+        remaining_ergs: u32,
+        decommitment_cost: u32,
+    },
+}
+
+impl core::fmt::Display for FarCallErgsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FarCallErgsError::StackDepthExceeded { stack_depth } => write!(
+                f,
+                "callstack already has {} frames, while at most {} are allowed",
+                stack_depth, VM_MAX_STACK_DEPTH
+            ),
+            FarCallErgsError::NotEnoughErgsForDecommitment {
+                remaining_ergs,
+                decommitment_cost,
+            } => write!(
+                f,
+                "decommitment costs {} ergs, while only {} are remaining",
+                decommitment_cost, remaining_ergs
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FarCallErgsError {}
+
+/// Ergs paid to the code decommitter to load the code, on top of the far call opcode price.
+/// Sorting of decommitment requests (`CODE_DECOMMITTER_SORTER_COST_IN_ERGS`, that is the same
+/// as `CODE_DECOMMITMENT_SORTER_COST_IN_ERGS`) is not included, as every far call pays for it
+/// in `PriceBreakdown::sorters` of the opcode price, whether the code is decommitted or not
+pub const fn decommitment_cost(code_length_in_words: u16, already_decommitted: bool) -> u32 {
+    if already_decommitted {
+        0
+    } else {
+        code_length_in_words as u32 * ERGS_PER_CODE_WORD_DECOMMITTMENT
+    }
+}
+
+impl ContractCodeSha256Storage {
+    pub const fn decommitment_cost(&self, already_decommitted: bool) -> u32 {
+        decommitment_cost(self.code_length_in_words, already_decommitted)
+    }
+}
+
+impl VersionedHashGeneric<ContractCodeSha256> {
+    pub fn decommitment_cost(&self, already_decommitted: bool) -> u32 {
+        self.layout_ref().decommitment_cost(already_decommitted)
+    }
+}
+
+/// Returns `None` if the input is not a valid versioned code hash: it has an unknown version
+/// or construction marker, or a length that bytecode can not have
+pub fn decommitment_cost_for_versioned_hash(
+    versioned_hash: [u8; 32],
+    already_decommitted: bool,
+) -> Option<u32> {
+    let hash = VersionedHashGeneric::<ContractCodeSha256>::try_create_from_raw(versioned_hash)?;
+    let code_length_in_words = hash.layout_ref().code_length_in_words as usize;
+    check_bytecode_length_for_mode::<8, EncodingModeProduction>(code_length_in_words).ok()?;
+
+    Some(hash.decommitment_cost(already_decommitted))
+}
+
+/// Splits the ergs of the caller between the caller and the callee. If the call fails
+/// the caller keeps all the ergs, except for the price of the far call opcode
+pub fn far_call_ergs_split(
+    params: &FarCallErgsParams,
+) -> Result<FarCallErgsSplit, FarCallErgsError> {
+    if params.stack_depth >= VM_MAX_STACK_DEPTH {
+        return Err(FarCallErgsError::StackDepthExceeded {
+            stack_depth: params.stack_depth,
+        });
+    }
+
+    let decommitment_cost =
+        decommitment_cost(params.code_length_in_words, params.code_already_decommitted);
+    let remaining_ergs = params
+        .caller_remaining_ergs
+        .checked_sub(decommitment_cost)
+        .ok_or(FarCallErgsError::NotEnoughErgsForDecommitment {
+            remaining_ergs: params.caller_remaining_ergs,
+            decommitment_cost,
+        })?;
+
+    let max_passable = remaining_ergs - remaining_ergs / FAR_CALL_ERGS_RETAINED_FRACTION;
+    let ergs_passed = core::cmp::min(params.ergs_passed, max_passable);
+    let ergs_retained = remaining_ergs - ergs_passed;
+
+    let (callee_stipend, callee_stipend_min_used) = if params.is_system_call
+        && params.callee_address == Address::from_low_u64_be(ADDRESS_MSG_VALUE as u64)
+    {
+        (
+            MSG_VALUE_SIMULATOR_ADDITIVE_COST,
+            MSG_VALUE_SIMULATOR_MIN_USED_ERGS,
+        )
+    } else {
+        (0, 0)
+    };
+
+    Ok(FarCallErgsSplit {
+        decommitment_cost,
+        ergs_passed,
+        ergs_retained,
+        callee_stipend,
+        callee_stipend_min_used,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_ergs() {
+        let mut params = FarCallErgsParams {
+            caller_remaining_ergs: 64_000 + 10 * ERGS_PER_CODE_WORD_DECOMMITTMENT,
+            stack_depth: 1,
+            ergs_passed: u32::MAX,
+            callee_address: Address::from_low_u64_be(0x10000),
+            is_system_call: false,
+            code_length_in_words: 10,
+            code_already_decommitted: false,
+        };
+        let split = far_call_ergs_split(&params).unwrap();
+        assert_eq!(
+            split.decommitment_cost,
+            10 * ERGS_PER_CODE_WORD_DECOMMITTMENT
+        );
+        assert_eq!(split.ergs_passed, 63_000);
+        assert_eq!(split.ergs_retained, 1_000);
+        assert_eq!(split.callee_stipend, 0);
+        assert_eq!(split.callee_ergs(), 63_000);
+        assert_eq!(split.max_stipend_refund(), 0);
+
+        params.code_already_decommitted = true;
+        params.ergs_passed = 100;
+        params.callee_address = Address::from_low_u64_be(ADDRESS_MSG_VALUE as u64);
+        params.is_system_call = true;
+        let split = far_call_ergs_split(&params).unwrap();
+        assert_eq!(split.decommitment_cost, 0);
+        assert_eq!(split.ergs_passed, 100);
+        assert_eq!(split.ergs_retained, params.caller_remaining_ergs - 100);
+        assert_eq!(split.callee_stipend, MSG_VALUE_SIMULATOR_ADDITIVE_COST);
+        assert_eq!(split.callee_ergs(), 100 + MSG_VALUE_SIMULATOR_ADDITIVE_COST);
+        assert_eq!(
+            split.max_stipend_refund(),
+            MSG_VALUE_SIMULATOR_ADDITIVE_COST - MSG_VALUE_SIMULATOR_MIN_USED_ERGS
+        );
+
+        // only system calls get the stipend
+        params.is_system_call = false;
+        assert_eq!(far_call_ergs_split(&params).unwrap().callee_stipend, 0);
+
+        params.code_already_decommitted = false;
+        params.caller_remaining_ergs = 1;
+        assert_eq!(
+            far_call_ergs_split(&params),
+            Err(FarCallErgsError::NotEnoughErgsForDecommitment {
+                remaining_ergs: 1,
+                decommitment_cost: 10 * ERGS_PER_CODE_WORD_DECOMMITTMENT
+            })
+        );
+    }
+
+    #[test]
+    fn stack_depth_limit() {
+        let mut params = FarCallErgsParams {
+            caller_remaining_ergs: 64_000,
+            stack_depth: VM_MAX_STACK_DEPTH - 1,
+            ergs_passed: 100,
+            callee_address: Address::from_low_u64_be(0x10000),
+            is_system_call: false,
+            code_length_in_words: 1,
+            code_already_decommitted: true,
+        };
+        assert!(far_call_ergs_split(&params).is_ok());
+
+        params.stack_depth = VM_MAX_STACK_DEPTH;
+        assert_eq!(
+            far_call_ergs_split(&params),
+            Err(FarCallErgsError::StackDepthExceeded {
+                stack_depth: VM_MAX_STACK_DEPTH
+            })
+        );
+    }
+
+    #[test]
+    fn far_call_price_includes_call_like_cost() {
+        use crate::{FarCallOpcode, Opcode, CALL_LIKE_ERGS_COST};
+
+        let breakdown = Opcode::FarCall(FarCallOpcode::Normal).price_breakdown();
+        assert_eq!(breakdown.call_like, CALL_LIKE_ERGS_COST);
+    }
+
+    #[test]
+    fn decommitment_cost_from_hash() {
+        let hash = VersionedHashGeneric::<ContractCodeSha256>::from_digest_and_preimage_num_words(
+            [0u8; 32], 7,
+        );
+        let raw = hash.serialize().unwrap();
+
+        assert_eq!(
+            decommitment_cost_for_versioned_hash(raw, false),
+            Some(7 * ERGS_PER_CODE_WORD_DECOMMITTMENT)
+        );
+        assert_eq!(decommitment_cost_for_versioned_hash(raw, true), Some(0));
+
+        let under_construction = hash.mark_as_under_construction().serialize().unwrap();
+        assert_eq!(
+            decommitment_cost_for_versioned_hash(under_construction, false),
+            Some(7 * ERGS_PER_CODE_WORD_DECOMMITTMENT)
+        );
+
+        let mut unknown_version = raw;
+        unknown_version[0] = 0xff;
+        assert_eq!(
+            decommitment_cost_for_versioned_hash(unknown_version, false),
+            None
+        );
+
+        let mut unknown_marker = raw;
+        unknown_marker[1] = 0xff;
+        assert_eq!(
+            decommitment_cost_for_versioned_hash(unknown_marker, false),
+            None
+        );
+
+        for num_words in [0, 8] {
+            let hash =
+                VersionedHashGeneric::<ContractCodeSha256>::from_digest_and_preimage_num_words(
+                    [0u8; 32], num_words,
+                );
+            assert_eq!(
+                decommitment_cost_for_versioned_hash(hash.serialize().unwrap(), false),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn far_call_price_includes_decommitment_sorting() {
+        use crate::circuit_prices::CODE_DECOMMITMENT_SORTER_COST_IN_ERGS;
+        use crate::{FarCallOpcode, Opcode};
+
+        let schedule = crate::PricingSchedule {
+            storage_sorter_cost_in_ergs: 0,
+            ..crate::PricingSchedule::default()
+        };
+        let breakdown = Opcode::FarCall(FarCallOpcode::Normal).price_breakdown_with(&schedule);
+        assert_eq!(breakdown.sorters, CODE_DECOMMITMENT_SORTER_COST_IN_ERGS);
+    }
+}
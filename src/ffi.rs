@@ -0,0 +1,427 @@
+// C interface for the production encoding mode. All the buffers are owned by the caller,
+// so nothing allocated here ever crosses the boundary and there is nothing to free.
+// Keep `include/zkevm_opcode_defs.h` in sync, it's generated with
+// `cbindgen --config cbindgen.toml --output include/zkevm_opcode_defs.h`
+
+use ethereum_types::U256;
+
+use crate::decoding::{EncodingModeProduction, VmEncodingMode};
+use crate::{
+    BytecodeHasher, DecodedOpcode, FarCallABI, FatPointer, ImmMemHandlerFlags, Opcode,
+    OpcodeVariant, Operand, RegOrImmFlags, RetABI, ALL_CONDITIONS,
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ZkevmStatus {
+    Ok = 0,
+    NullPointer = 1,
+    /// Fields of the instruction don't describe any variant of the default ISA version
+    InvalidInstruction = 2,
+    /// Length of the bytecode is not a multiple of 32, it has an even number of words or it's too long
+    InvalidBytecode = 3,
+}
+
+// operand kinds are plain integers, as C can put any value into an enum field
+pub const ZKEVM_OPERAND_REG_ONLY: u8 = 0;
+pub const ZKEVM_OPERAND_REG_OR_IMM: u8 = 1;
+pub const ZKEVM_OPERAND_FULL: u8 = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ZkevmOperand {
+    /// One of `ZKEVM_OPERAND_*`
+    pub kind: u8,
+    /// `encoding_byte` of `RegOrImmFlags` or `ImmMemHandlerFlags`, 0 for `RegOnly`
+    pub mode: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ZkevmDecodedInstruction {
+    /// `Opcode::variant_idx`
+    pub opcode: u8,
+    /// `Opcode::materialize_subvariant_idx`
+    pub sub_opcode: u8,
+    pub src0_operand: ZkevmOperand,
+    pub dst0_operand: ZkevmOperand,
+    pub flags: [bool; 2],
+    /// `Condition::variant_index`
+    pub condition: u8,
+    pub src0_reg_idx: u8,
+    pub src1_reg_idx: u8,
+    pub dst0_reg_idx: u8,
+    pub dst1_reg_idx: u8,
+    pub imm_0: u16,
+    pub imm_1: u16,
+    /// Ignored on encoding
+    pub ergs_price: u32,
+    /// Ignored on encoding
+    pub requires_kernel_mode: bool,
+    /// Ignored on encoding
+    pub can_be_used_in_static_context: bool,
+    /// Ignored on encoding. Non-canonical encodings are decoded, but the VM would not execute them
+    pub canonical: bool,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ZkevmFatPointer {
+    pub offset: u32,
+    pub memory_page: u32,
+    pub start: u32,
+    pub length: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ZkevmFarCallAbi {
+    pub memory_quasi_fat_pointer: ZkevmFatPointer,
+    pub ergs_passed: u32,
+    pub shard_id: u8,
+    /// `FarCallForwardPageType` as `u8`
+    pub forwarding_mode: u8,
+    pub constructor_call: bool,
+    pub to_system: bool,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ZkevmRetAbi {
+    pub memory_quasi_fat_pointer: ZkevmFatPointer,
+    /// `RetForwardPageType` as `u8`
+    pub page_forwarding_mode: u8,
+}
+
+impl From<Operand> for ZkevmOperand {
+    fn from(value: Operand) -> Self {
+        match value {
+            Operand::RegOnly => ZkevmOperand {
+                kind: ZKEVM_OPERAND_REG_ONLY,
+                mode: 0,
+            },
+            Operand::RegOrImm(mode) => ZkevmOperand {
+                kind: ZKEVM_OPERAND_REG_OR_IMM,
+                mode: mode.encoding_byte(),
+            },
+            Operand::Full(mode) => ZkevmOperand {
+                kind: ZKEVM_OPERAND_FULL,
+                mode: mode.encoding_byte(),
+            },
+        }
+    }
+}
+
+impl ZkevmOperand {
+    fn to_operand(self) -> Option<Operand> {
+        match self.kind {
+            ZKEVM_OPERAND_REG_ONLY if self.mode == 0 => Some(Operand::RegOnly),
+            ZKEVM_OPERAND_REG_OR_IMM => RegOrImmFlags::all_variants()
+                .into_iter()
+                .find(|el| el.encoding_byte() == self.mode)
+                .map(Operand::RegOrImm),
+            ZKEVM_OPERAND_FULL => ImmMemHandlerFlags::all_variants()
+                .into_iter()
+                .find(|el| el.encoding_byte() == self.mode)
+                .map(Operand::Full),
+            _ => None,
+        }
+    }
+}
+
+impl From<FatPointer> for ZkevmFatPointer {
+    fn from(value: FatPointer) -> Self {
+        ZkevmFatPointer {
+            offset: value.offset,
+            memory_page: value.memory_page,
+            start: value.start,
+            length: value.length,
+        }
+    }
+}
+
+impl ZkevmDecodedInstruction {
+    pub fn from_raw(raw: u64) -> Self {
+        let (decoded, _) =
+            EncodingModeProduction::parse_preliminary_variant_and_absolute_number(raw);
+        let variant = decoded.variant;
+
+        ZkevmDecodedInstruction {
+            opcode: variant.opcode.variant_idx() as u8,
+            sub_opcode: variant.opcode.materialize_subvariant_idx() as u8,
+            src0_operand: variant.src0_operand_type.into(),
+            dst0_operand: variant.dst0_operand_type.into(),
+            flags: variant.flags,
+            condition: decoded.condition.variant_index() as u8,
+            src0_reg_idx: decoded.src0_reg_idx,
+            src1_reg_idx: decoded.src1_reg_idx,
+            dst0_reg_idx: decoded.dst0_reg_idx,
+            dst1_reg_idx: decoded.dst1_reg_idx,
+            imm_0: decoded.imm_0,
+            imm_1: decoded.imm_1,
+            ergs_price: variant.ergs_price(),
+            requires_kernel_mode: variant.requires_kernel_mode(),
+            can_be_used_in_static_context: variant.can_be_used_in_static_context(),
+            canonical: EncodingModeProduction::is_canonical_encoding(raw),
+        }
+    }
+
+    /// Returns `None` if the fields don't describe an encodable instruction
+    pub fn to_raw(&self) -> Option<u64> {
+        let opcode = Opcode::all_variants().into_iter().find(|el| {
+            el.variant_idx() == self.opcode as usize
+                && el.materialize_subvariant_idx() == self.sub_opcode as usize
+        })?;
+        let variant = OpcodeVariant {
+            opcode,
+            src0_operand_type: self.src0_operand.to_operand()?,
+            dst0_operand_type: self.dst0_operand.to_operand()?,
+            flags: self.flags,
+        };
+        let condition = ALL_CONDITIONS.get(self.condition as usize).copied()?;
+
+        let decoded: DecodedOpcode<8, EncodingModeProduction> = DecodedOpcode {
+            variant,
+            condition,
+            src0_reg_idx: self.src0_reg_idx,
+            src1_reg_idx: self.src1_reg_idx,
+            dst0_reg_idx: self.dst0_reg_idx,
+            dst1_reg_idx: self.dst1_reg_idx,
+            imm_0: self.imm_0,
+            imm_1: self.imm_1,
+        };
+
+        decoded.try_serialize_as_integer().ok()
+    }
+}
+
+/// Decodes the instruction from its integer representation, as stored in the bytecode
+/// in big endian order. Any value is decodable, check `canonical` to filter out the ones
+/// that the VM would reject
+///
+/// # Safety
+///
+/// `out` must be either null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn zkevm_decode_instruction(
+    raw: u64,
+    out: *mut ZkevmDecodedInstruction,
+) -> ZkevmStatus {
+    if out.is_null() {
+        return ZkevmStatus::NullPointer;
+    }
+    out.write(ZkevmDecodedInstruction::from_raw(raw));
+
+    ZkevmStatus::Ok
+}
+
+/// # Safety
+///
+/// `instruction` must be either null or valid for reads, `out` must be either null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn zkevm_encode_instruction(
+    instruction: *const ZkevmDecodedInstruction,
+    out: *mut u64,
+) -> ZkevmStatus {
+    if instruction.is_null() || out.is_null() {
+        return ZkevmStatus::NullPointer;
+    }
+
+    match instruction.read().to_raw() {
+        Some(raw) => {
+            out.write(raw);
+            ZkevmStatus::Ok
+        }
+        None => ZkevmStatus::InvalidInstruction,
+    }
+}
+
+/// Writes the versioned code hash of the bytecode into `out`
+///
+/// # Safety
+///
+/// `bytecode` must be valid for reads of `length` bytes (or may be null if `length` is 0),
+/// `out` must be either null or valid for writes of 32 bytes
+#[no_mangle]
+pub unsafe extern "C" fn zkevm_bytecode_hash(
+    bytecode: *const u8,
+    length: usize,
+    out: *mut u8,
+) -> ZkevmStatus {
+    if (bytecode.is_null() && length != 0) || out.is_null() {
+        return ZkevmStatus::NullPointer;
+    }
+    let bytecode = if length == 0 {
+        &[]
+    } else {
+        core::slice::from_raw_parts(bytecode, length)
+    };
+
+    let mut hasher = BytecodeHasher::new();
+    hasher.update(bytecode);
+    match hasher.finalize().ok().and_then(|el| el.serialize()) {
+        Some(hash) => {
+            core::ptr::copy_nonoverlapping(hash.as_ptr(), out, hash.len());
+            ZkevmStatus::Ok
+        }
+        None => ZkevmStatus::InvalidBytecode,
+    }
+}
+
+unsafe fn read_word(word: *const u8) -> U256 {
+    U256::from_big_endian(core::slice::from_raw_parts(word, 32))
+}
+
+/// ABI words are passed as 32 bytes in big endian order
+///
+/// # Safety
+///
+/// `word` must be either null or valid for reads of 32 bytes, `out` must be either null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn zkevm_parse_fat_pointer(
+    word: *const u8,
+    out: *mut ZkevmFatPointer,
+) -> ZkevmStatus {
+    if word.is_null() || out.is_null() {
+        return ZkevmStatus::NullPointer;
+    }
+    out.write(FatPointer::from_u256(read_word(word)).into());
+
+    ZkevmStatus::Ok
+}
+
+/// # Safety
+///
+/// `word` must be either null or valid for reads of 32 bytes, `out` must be either null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn zkevm_parse_far_call_abi(
+    word: *const u8,
+    out: *mut ZkevmFarCallAbi,
+) -> ZkevmStatus {
+    if word.is_null() || out.is_null() {
+        return ZkevmStatus::NullPointer;
+    }
+    let abi = FarCallABI::from_u256(read_word(word));
+    out.write(ZkevmFarCallAbi {
+        memory_quasi_fat_pointer: abi.memory_quasi_fat_pointer.into(),
+        ergs_passed: abi.ergs_passed,
+        shard_id: abi.shard_id,
+        forwarding_mode: abi.forwarding_mode as u8,
+        constructor_call: abi.constructor_call,
+        to_system: abi.to_system,
+    });
+
+    ZkevmStatus::Ok
+}
+
+/// # Safety
+///
+/// `word` must be either null or valid for reads of 32 bytes, `out` must be either null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn zkevm_parse_ret_abi(
+    word: *const u8,
+    out: *mut ZkevmRetAbi,
+) -> ZkevmStatus {
+    if word.is_null() || out.is_null() {
+        return ZkevmStatus::NullPointer;
+    }
+    let abi = RetABI::from_u256(read_word(word));
+    out.write(ZkevmRetAbi {
+        memory_quasi_fat_pointer: abi.memory_quasi_fat_pointer.into(),
+        page_forwarding_mode: abi.page_forwarding_mode as u8,
+    });
+
+    ZkevmStatus::Ok
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bytecode_to_code_hash, OPCODES_TABLE, OPCODE_TO_CANONICAL_INDEX_LOOKUP_MAP};
+
+    #[test]
+    fn encode_decode() {
+        for (idx, variant) in OPCODES_TABLE.iter().enumerate() {
+            if OPCODE_TO_CANONICAL_INDEX_LOOKUP_MAP[variant] != idx {
+                continue;
+            }
+            let raw = (idx as u64)
+                | (0x21u64 << crate::SRC_REGS_SHIFT)
+                | (0x43u64 << crate::DST_REGS_SHIFT)
+                | (0xbeefu64 << 48);
+
+            let mut decoded = ZkevmDecodedInstruction::from_raw(0);
+            let mut encoded = 0u64;
+            unsafe {
+                assert_eq!(zkevm_decode_instruction(raw, &mut decoded), ZkevmStatus::Ok);
+                assert_eq!(
+                    zkevm_encode_instruction(&decoded, &mut encoded),
+                    ZkevmStatus::Ok
+                );
+            }
+            assert_eq!(encoded, raw, "{}", variant);
+            assert!(decoded.canonical);
+            assert_eq!(decoded.ergs_price, variant.ergs_price());
+        }
+
+        let mut invalid = ZkevmDecodedInstruction::from_raw(0);
+        invalid.sub_opcode = 100;
+        assert_eq!(invalid.to_raw(), None);
+        let mut invalid = ZkevmDecodedInstruction::from_raw(0);
+        invalid.src0_reg_idx = 16;
+        assert_eq!(invalid.to_raw(), None);
+        unsafe {
+            assert_eq!(
+                zkevm_decode_instruction(0, core::ptr::null_mut()),
+                ZkevmStatus::NullPointer
+            );
+        }
+    }
+
+    #[test]
+    fn hash_and_abi() {
+        let bytecode = [[7u8; 32]; 3];
+        let flat: alloc::vec::Vec<u8> = bytecode.iter().flatten().copied().collect();
+        let mut hash = [0u8; 32];
+        unsafe {
+            assert_eq!(
+                zkevm_bytecode_hash(flat.as_ptr(), flat.len(), hash.as_mut_ptr()),
+                ZkevmStatus::Ok
+            );
+            assert_eq!(
+                zkevm_bytecode_hash(flat.as_ptr(), 64, hash.as_mut_ptr()),
+                ZkevmStatus::InvalidBytecode
+            );
+        }
+        assert_eq!(hash, bytecode_to_code_hash(&bytecode).unwrap());
+
+        let pointer = FatPointer {
+            offset: 1,
+            memory_page: 2,
+            start: 3,
+            length: 4,
+        };
+        let mut word = [0u8; 32];
+        pointer.to_u256().to_big_endian(&mut word);
+        let mut parsed = ZkevmFatPointer::from(FatPointer::empty());
+        let mut far_call = core::mem::MaybeUninit::<ZkevmFarCallAbi>::uninit();
+        let mut ret = core::mem::MaybeUninit::<ZkevmRetAbi>::uninit();
+        unsafe {
+            assert_eq!(
+                zkevm_parse_fat_pointer(word.as_ptr(), &mut parsed),
+                ZkevmStatus::Ok
+            );
+            assert_eq!(
+                zkevm_parse_far_call_abi(word.as_ptr(), far_call.as_mut_ptr()),
+                ZkevmStatus::Ok
+            );
+            assert_eq!(
+                zkevm_parse_ret_abi(word.as_ptr(), ret.as_mut_ptr()),
+                ZkevmStatus::Ok
+            );
+            assert_eq!(far_call.assume_init().memory_quasi_fat_pointer, parsed);
+            assert_eq!(ret.assume_init().memory_quasi_fat_pointer, parsed);
+        }
+        assert_eq!(parsed, pointer.into());
+    }
+}
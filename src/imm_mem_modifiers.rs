@@ -1,4 +1,5 @@
 #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ImmMemHandlerFlags {
     UseRegOnly = 0,
@@ -78,6 +79,7 @@ impl ImmMemHandlerFlags {
 }
 
 #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum RegOrImmFlags {
     UseRegOnly = 0,
@@ -0,0 +1,45 @@
+use zkevm_opcode_defs::{diff_isa_versions, ISAVersion, ALL_ISA_VERSIONS, DEFAULT_ISA_VERSION};
+
+const USAGE: &str = "Usage: isa_diff [<from version> <to version>]
+
+    Lists the decoding table entries that were added, removed or replaced between two ISA versions.
+    Compares the version preceding the default one with the default one if no versions are given";
+
+fn parse_version(arg: &str) -> Result<ISAVersion, String> {
+    let version = arg
+        .parse::<u8>()
+        .map(ISAVersion)
+        .map_err(|_| format!("`{}` is not a valid version number", arg))?;
+    if !ALL_ISA_VERSIONS.contains(&version) {
+        return Err(format!("unknown ISA version {}", version.0));
+    }
+
+    Ok(version)
+}
+
+fn parse_args() -> Result<(ISAVersion, ISAVersion), String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|el| el == "--help" || el == "-h") {
+        println!("{}", USAGE);
+        std::process::exit(0);
+    }
+
+    match args.as_slice() {
+        [] => Ok((
+            ISAVersion(DEFAULT_ISA_VERSION.0.saturating_sub(1)),
+            DEFAULT_ISA_VERSION,
+        )),
+        [from, to] => Ok((parse_version(from)?, parse_version(to)?)),
+        _ => Err("expected exactly two versions".to_owned()),
+    }
+}
+
+fn main() {
+    let (from, to) = parse_args().unwrap_or_else(|err| {
+        eprintln!("{}\n\n{}", err, USAGE);
+        std::process::exit(2);
+    });
+
+    let diff = diff_isa_versions(from, to).expect("versions are checked when parsing");
+    print!("{}", diff);
+}
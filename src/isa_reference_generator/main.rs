@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+mod reference;
+
+use reference::isa_reference_markdown;
+
+const USAGE: &str = "Usage: isa_reference_generator [--output <file>] [--check]
+
+    --output <file>   write the Markdown reference into the file instead of stdout,
+                      the checked in one is `docs/isa_reference.md`
+    --check           do not write anything, exit with non-zero code if the file is outdated";
+
+struct Args {
+    output: Option<PathBuf>,
+    check: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        output: None,
+        check: false,
+    };
+
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--output" => {
+                let path = it.next().ok_or("`--output` requires a path")?;
+                args.output = Some(PathBuf::from(path));
+            }
+            "--check" => args.check = true,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+
+    if args.check && args.output.is_none() {
+        return Err("`--check` requires `--output`".to_owned());
+    }
+
+    Ok(args)
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|err| {
+        eprintln!("{}\n\n{}", err, USAGE);
+        std::process::exit(2);
+    });
+
+    let reference = isa_reference_markdown();
+
+    match args.output {
+        Some(path) if args.check => {
+            let existing = std::fs::read_to_string(&path).unwrap_or_default();
+            if existing != reference {
+                eprintln!("{} is outdated", path.display());
+                std::process::exit(1);
+            }
+            println!("{} is up to date", path.display());
+        }
+        Some(path) => {
+            std::fs::write(&path, reference).unwrap_or_else(|err| {
+                eprintln!("failed to write {}: {}", path.display(), err);
+                std::process::exit(2);
+            });
+        }
+        None => print!("{}", reference),
+    }
+}
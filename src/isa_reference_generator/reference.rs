@@ -0,0 +1,233 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use zkevm_opcode_defs::{
+    all_opcode_prototypes, compute_decoding_format, synthesize_opcode_decoding_tables, ISAVersion,
+    ImmMemHandlerFlags, Opcode, OpcodeSpec, OpcodeVariant, OperandSpec, RegOrImmFlags,
+    ALL_CONDITIONS, ALL_ISA_VERSIONS, CAN_BE_USED_IN_STATIC_CONTEXT_FLAG_IDX, DEFAULT_ISA_VERSION,
+    EXPLICIT_PANIC_FLAG_IDX, KERNER_MODE_FLAG_IDX, LATEST_ISA_VERSION, OPCODES_TABLE_WIDTH,
+    OPCODE_PROTOTYPES,
+};
+
+fn introduced_in(opcode: &Opcode) -> Option<ISAVersion> {
+    ALL_ISA_VERSIONS
+        .iter()
+        .copied()
+        .find(|el| opcode.is_available_for_version(*el))
+}
+
+fn format_flags(spec: &OpcodeSpec) -> String {
+    if spec.flags.is_empty() {
+        return "-".to_owned();
+    }
+
+    spec.flags
+        .iter()
+        .map(|el| format!("`{}` ({}): {}", el.name, el.index, el.description))
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+fn format_immediates(spec: &OpcodeSpec) -> String {
+    if spec.immediates.is_empty() {
+        return "-".to_owned();
+    }
+
+    spec.immediates
+        .iter()
+        .map(|el| format!("`imm_{}` `{}`: {}", el.index, el.name, el.description))
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+fn format_operands(spec: &[OperandSpec]) -> String {
+    if spec.is_empty() {
+        return "-".to_owned();
+    }
+
+    spec.iter()
+        .map(|el| format!("`{}`: {:?}", el.name, el.operand))
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn write_opcode_section(
+    out: &mut String,
+    idx: usize,
+    prototype: &Opcode,
+    tables: &[(ISAVersion, Vec<OpcodeVariant>)],
+) -> std::fmt::Result {
+    let props = &OPCODE_PROTOTYPES[idx];
+    writeln!(out, "### {}\n", props.name())?;
+
+    let mut variant_indices = BTreeSet::new();
+    for version in ALL_ISA_VERSIONS.iter() {
+        for el in props.variants_data(*version) {
+            variant_indices.insert(el.variant_idx);
+        }
+    }
+
+    writeln!(
+        out,
+        "| Variant | Since | Price (ergs) | Kernel mode only | Static context | Flags | Inputs | Outputs | Immediates |"
+    )?;
+    writeln!(out, "|---|---|---|---|---|---|---|---|---|")?;
+    for variant_idx in variant_indices.into_iter() {
+        let opcode =
+            prototype.materialize_subvariant_from_prototype(variant_idx, &LATEST_ISA_VERSION);
+        let spec = match opcode.spec(LATEST_ISA_VERSION) {
+            Some(spec) => spec,
+            None => continue,
+        };
+        writeln!(
+            out,
+            "| `{:?}` | {} | {} | {} | {} | {} | {} | {} | {} |",
+            opcode,
+            introduced_in(&opcode).expect("opcode is available").0,
+            opcode.ergs_price(),
+            yes_no(opcode.requires_kernel_mode()),
+            yes_no(opcode.can_be_used_in_static_context()),
+            format_flags(&spec),
+            format_operands(&spec.inputs),
+            format_operands(&spec.outputs),
+            format_immediates(&spec),
+        )?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, "Addressing modes:\n")?;
+    for (version, table) in tables.iter() {
+        let mut src0 = BTreeSet::new();
+        let mut dst0 = BTreeSet::new();
+        for el in table.iter() {
+            if el.opcode.variant_idx() == prototype.variant_idx() {
+                src0.insert(format!("{:?}", el.src0_operand_type));
+                dst0.insert(format!("{:?}", el.dst0_operand_type));
+            }
+        }
+        writeln!(
+            out,
+            "- version {}: src0 {}; dst0 {}",
+            version.0,
+            src0.into_iter().collect::<Vec<_>>().join(", "),
+            dst0.into_iter().collect::<Vec<_>>().join(", ")
+        )?;
+    }
+    writeln!(out)?;
+
+    Ok(())
+}
+
+fn write_isa_reference(out: &mut String) -> std::fmt::Result {
+    writeln!(out, "# zkEVM ISA reference\n")?;
+    writeln!(
+        out,
+        "Generated from the opcode definitions, do not edit manually.\n"
+    )?;
+
+    writeln!(out, "## Versions\n")?;
+    for version in ALL_ISA_VERSIONS.iter() {
+        let suffix = if *version == DEFAULT_ISA_VERSION {
+            " (default)"
+        } else {
+            ""
+        };
+        writeln!(out, "- {}{}", version.0, suffix)?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, "## Encoding\n")?;
+    for version in ALL_ISA_VERSIONS.iter() {
+        writeln!(out, "Version {}:\n", version.0)?;
+        writeln!(out, "```\n{}\n```\n", compute_decoding_format(*version))?;
+    }
+    writeln!(
+        out,
+        "Auxiliary flags that follow the opcode description bits:\n"
+    )?;
+    writeln!(out, "- {}: requires kernel mode", KERNER_MODE_FLAG_IDX)?;
+    writeln!(
+        out,
+        "- {}: can be used in static context",
+        CAN_BE_USED_IN_STATIC_CONTEXT_FLAG_IDX
+    )?;
+    writeln!(out, "- {}: explicit panic", EXPLICIT_PANIC_FLAG_IDX)?;
+    writeln!(out)?;
+
+    writeln!(out, "## Conditions\n")?;
+    for el in ALL_CONDITIONS.iter() {
+        writeln!(out, "- {}: `{:?}`", el.variant_index(), el)?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, "## Addressing modes\n")?;
+    writeln!(out, "| Mode | Index | src | dst | Uses memory |")?;
+    writeln!(out, "|---|---|---|---|---|")?;
+    for el in ImmMemHandlerFlags::all_variants().iter() {
+        writeln!(
+            out,
+            "| `Full({:?})` | {} | yes | {} | {} |",
+            el,
+            el.variant_index(),
+            yes_no(el.is_allowed_for_dst()),
+            yes_no(el.is_memory_used())
+        )?;
+    }
+    for el in RegOrImmFlags::all_variants().iter() {
+        writeln!(
+            out,
+            "| `RegOrImm({:?})` | {} | yes | {} | {} |",
+            el,
+            el.variant_index(),
+            yes_no(el.is_allowed_for_dst()),
+            yes_no(el.is_memory_used())
+        )?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, "## Opcodes\n")?;
+    let tables: Vec<_> = ALL_ISA_VERSIONS
+        .iter()
+        .map(|el| {
+            (
+                *el,
+                synthesize_opcode_decoding_tables(OPCODES_TABLE_WIDTH, *el),
+            )
+        })
+        .collect();
+    for (idx, prototype) in all_opcode_prototypes().iter().enumerate() {
+        write_opcode_section(out, idx, prototype, &tables)?;
+    }
+
+    Ok(())
+}
+
+pub fn isa_reference_markdown() -> String {
+    let mut result = String::new();
+    write_isa_reference(&mut result).expect("writing into a string can not fail");
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reference_covers_all_opcodes() {
+        let reference = isa_reference_markdown();
+        for el in OPCODE_PROTOTYPES.iter() {
+            assert!(reference.contains(&format!("### {}\n", el.name())));
+        }
+        assert!(reference.contains("`Log(StorageWrite)`"));
+        assert!(reference.contains("`UMA(FatPointerRead)`"));
+    }
+}
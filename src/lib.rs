@@ -1,15 +1,47 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "spin_no_std", test)))]
+compile_error!("either `std` or `spin_no_std` feature must be enabled");
+
 pub const REGISTERS_COUNT: usize = 15;
 
+pub mod bytecode_validation;
+pub mod circuit_usage;
 pub mod decoding;
 pub mod definitions;
+pub mod far_call_ergs;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod imm_mem_modifiers;
+pub mod memory_growth;
 pub mod opcode;
+pub mod pricing;
+pub mod pubdata;
+#[cfg(feature = "pyo3")]
+pub mod python;
 pub mod system_params;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "serde")]
+mod serde_utils;
 
 pub mod circuit_prices;
 
-use std::collections::HashMap;
+/// Hash map with `std`, ordered map otherwise. The maps are only used for lookups,
+/// so the iteration order does not matter
+#[cfg(feature = "std")]
+pub type OpcodeVariantMap<V> = std::collections::HashMap<OpcodeVariant, V>;
+#[cfg(not(feature = "std"))]
+pub type OpcodeVariantMap<V> = alloc::collections::BTreeMap<OpcodeVariant, V>;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 use circuit_prices::CODE_DECOMMITMENT_COST_PER_WORD_IN_ERGS;
 use circuit_prices::CODE_DECOMMITTER_SORTER_COST_IN_ERGS;
@@ -23,9 +55,15 @@ pub use k256;
 pub use sha2;
 pub use sha3;
 
+pub use self::bytecode_validation::*;
+pub use self::circuit_usage::*;
 pub use self::definitions::*;
+pub use self::far_call_ergs::*;
 pub use self::imm_mem_modifiers::*;
+pub use self::memory_growth::*;
 pub use self::opcode::*;
+pub use self::pricing::*;
+pub use self::pubdata::*;
 pub use self::utils::*;
 
 pub use self::system_params::{
@@ -133,8 +171,8 @@ lazy_static! {
         synthesize_bit_decomposition_table(&*OPCODES_TABLE, DEFAULT_ISA_VERSION).try_into().unwrap()
     };
 
-    pub static ref OPCODE_TO_MONOTONIC_INDEX_NUMBER_MAP: HashMap<OpcodeVariant, VariantMonotonicNumber> = {
-        let mut result = HashMap::<OpcodeVariant, VariantMonotonicNumber>::new();
+    pub static ref OPCODE_TO_MONOTONIC_INDEX_NUMBER_MAP: OpcodeVariantMap<VariantMonotonicNumber> = {
+        let mut result = OpcodeVariantMap::<VariantMonotonicNumber>::new();
         for (idx, el) in OPCODES_TABLE.iter().enumerate() {
             if let Some(existing) = result.get(el) {
                 let usize_index = (*existing).into_usize();
@@ -147,8 +185,8 @@ lazy_static! {
         result
     };
 
-    pub static ref OPCODE_TO_CANONICAL_INDEX_LOOKUP_MAP: HashMap<OpcodeVariant, usize> = {
-        let mut result = HashMap::new();
+    pub static ref OPCODE_TO_CANONICAL_INDEX_LOOKUP_MAP: OpcodeVariantMap<usize> = {
+        let mut result = OpcodeVariantMap::new();
         for (idx, el) in OPCODES_TABLE.iter().enumerate() {
             if let Some(existing) = result.get(el) {
                 assert_eq!(OPCODES_TABLE[*existing], INVALID_OPCODE_VARIANT);
@@ -217,13 +255,7 @@ lazy_static! {
 
     // Preliminary pricing
     pub static ref OPCODES_PRICES: [u32; 1 << OPCODES_TABLE_WIDTH] = {
-        let mut result = Vec::with_capacity(1 << OPCODES_TABLE_WIDTH);
-        for opcode in OPCODES_TABLE.iter() {
-            let price = opcode.ergs_price();
-            result.push(price)
-        }
-
-        result.try_into().unwrap()
+        synthesize_opcodes_prices(&*OPCODES_TABLE, &PricingSchedule::default()).try_into().unwrap()
     };
 }
 
@@ -0,0 +1,288 @@
+use ethereum_types::U256;
+
+use crate::system_params::NEW_FRAME_MEMORY_STIPEND;
+use crate::{
+    FatPointer, UMAOpcode, LOG2_NUM_ADDRESSABLE_HEAP_BYTES, MAX_OFFSET_TO_DEREF,
+    MEMORY_GROWTH_ERGS_PER_BYTE,
+};
+
+/// Heap and aux heap can never grow beyond this bound
+pub const MAX_HEAP_BOUND: u32 = 1u32 << LOG2_NUM_ADDRESSABLE_HEAP_BYTES;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HeapKind {
+    Heap,
+    AuxHeap,
+}
+
+impl UMAOpcode {
+    /// Heap that can grow on access. Fat pointer reads only access already existing slices
+    pub const fn heap_kind(&self) -> Option<HeapKind> {
+        match self {
+            UMAOpcode::HeapRead | UMAOpcode::HeapWrite => Some(HeapKind::Heap),
+            UMAOpcode::AuxHeapRead | UMAOpcode::AuxHeapWrite => Some(HeapKind::AuxHeap),
+            UMAOpcode::FatPointerRead => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MemoryAccess {
+    /// 32 bytes starting from the offset, as accessed by `UMAOpcode`
+    Word { offset: U256 },
+    /// Slice passed to far call or returned from the frame
+    Slice(FatPointer),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MemoryGrowthError {
+    OffsetOutOfRange { offset: U256 },
+    SliceOutOfRange { start: u32, length: u32 },
+    BeyondAddressableHeap { required_bound: u32 },
+}
+
+impl core::fmt::Display for MemoryGrowthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MemoryGrowthError::OffsetOutOfRange { offset } => write!(
+                f,
+                "offset {} is beyond the maximum dereferenceable offset {}",
+                offset, MAX_OFFSET_TO_DEREF
+            ),
+            MemoryGrowthError::SliceOutOfRange { start, length } => write!(
+                f,
+                "slice of length {} starting at {} is beyond the addressable range",
+                length, start
+            ),
+            MemoryGrowthError::BeyondAddressableHeap { required_bound } => write!(
+                f,
+                "access requires the heap bound of {}, while only {} bytes are addressable",
+                required_bound, MAX_HEAP_BOUND
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MemoryGrowthError {}
+
+impl MemoryAccess {
+    /// The heap bound that is required for this access to be valid
+    pub fn required_bound(&self) -> Result<u32, MemoryGrowthError> {
+        let required_bound = match self {
+            MemoryAccess::Word { offset } => {
+                if *offset > MAX_OFFSET_TO_DEREF {
+                    return Err(MemoryGrowthError::OffsetOutOfRange { offset: *offset });
+                }
+
+                // can not overflow by the definition of `MAX_OFFSET_TO_DEREF`
+                offset.low_u32() + 32
+            }
+            MemoryAccess::Slice(pointer) => pointer.start.checked_add(pointer.length).ok_or(
+                MemoryGrowthError::SliceOutOfRange {
+                    start: pointer.start,
+                    length: pointer.length,
+                },
+            )?,
+        };
+
+        if required_bound > MAX_HEAP_BOUND {
+            return Err(MemoryGrowthError::BeyondAddressableHeap { required_bound });
+        }
+
+        Ok(required_bound)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MemoryGrowth {
+    pub new_bound: u32,
+    pub ergs: u32,
+}
+
+/// Computes the new bound and the ergs to pay for the access. The first `NEW_FRAME_MEMORY_STIPEND`
+/// bytes are free, so the bound below it is treated as the stipend itself
+pub fn memory_growth(
+    current_bound: u32,
+    access: &MemoryAccess,
+) -> Result<MemoryGrowth, MemoryGrowthError> {
+    let required_bound = access.required_bound()?;
+    let paid_bound = core::cmp::max(current_bound, NEW_FRAME_MEMORY_STIPEND);
+
+    let result = if required_bound > paid_bound {
+        MemoryGrowth {
+            new_bound: required_bound,
+            ergs: (required_bound - paid_bound) * MEMORY_GROWTH_ERGS_PER_BYTE,
+        }
+    } else {
+        MemoryGrowth {
+            new_bound: core::cmp::max(current_bound, required_bound),
+            ergs: 0,
+        }
+    };
+
+    Ok(result)
+}
+
+/// Bounds of the heap and aux heap of a single frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HeapBounds {
+    pub heap: u32,
+    pub aux_heap: u32,
+}
+
+impl HeapBounds {
+    pub const fn new_frame() -> Self {
+        Self {
+            heap: NEW_FRAME_MEMORY_STIPEND,
+            aux_heap: NEW_FRAME_MEMORY_STIPEND,
+        }
+    }
+
+    pub const fn bound(&self, kind: HeapKind) -> u32 {
+        match kind {
+            HeapKind::Heap => self.heap,
+            HeapKind::AuxHeap => self.aux_heap,
+        }
+    }
+
+    /// Grows the corresponding heap and returns the ergs to charge for it
+    pub fn grow(
+        &mut self,
+        kind: HeapKind,
+        access: &MemoryAccess,
+    ) -> Result<u32, MemoryGrowthError> {
+        let growth = memory_growth(self.bound(kind), access)?;
+        match kind {
+            HeapKind::Heap => self.heap = growth.new_bound,
+            HeapKind::AuxHeap => self.aux_heap = growth.new_bound,
+        }
+
+        Ok(growth.ergs)
+    }
+
+    /// Same as `grow`, but for the heap accessed by the opcode.
+    /// Returns 0 for opcodes that can not grow memory
+    pub fn grow_for_uma(
+        &mut self,
+        opcode: UMAOpcode,
+        offset: U256,
+    ) -> Result<u32, MemoryGrowthError> {
+        match opcode.heap_kind() {
+            Some(kind) => self.grow(kind, &MemoryAccess::Word { offset }),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Default for HeapBounds {
+    fn default() -> Self {
+        Self::new_frame()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn heap_growth() {
+        let mut bounds = HeapBounds::new_frame();
+
+        // within the stipend
+        let ergs = bounds
+            .grow_for_uma(UMAOpcode::HeapWrite, U256::from(64))
+            .unwrap();
+        assert_eq!(ergs, 0);
+        assert_eq!(bounds.heap, NEW_FRAME_MEMORY_STIPEND);
+
+        let offset = NEW_FRAME_MEMORY_STIPEND + 100;
+        let ergs = bounds
+            .grow_for_uma(UMAOpcode::AuxHeapRead, U256::from(offset))
+            .unwrap();
+        assert_eq!(ergs, (100 + 32) * MEMORY_GROWTH_ERGS_PER_BYTE);
+        assert_eq!(bounds.aux_heap, offset + 32);
+        assert_eq!(bounds.heap, NEW_FRAME_MEMORY_STIPEND);
+
+        // already paid
+        let ergs = bounds
+            .grow(
+                HeapKind::AuxHeap,
+                &MemoryAccess::Word {
+                    offset: U256::zero(),
+                },
+            )
+            .unwrap();
+        assert_eq!(ergs, 0);
+
+        let pointer = FatPointer {
+            start: NEW_FRAME_MEMORY_STIPEND,
+            length: 10,
+            ..FatPointer::empty()
+        };
+        let ergs = bounds
+            .grow(HeapKind::Heap, &MemoryAccess::Slice(pointer))
+            .unwrap();
+        assert_eq!(ergs, 10 * MEMORY_GROWTH_ERGS_PER_BYTE);
+    }
+
+    #[test]
+    fn out_of_range_access() {
+        let offset = MAX_OFFSET_TO_DEREF + U256::one();
+        assert_eq!(
+            memory_growth(0, &MemoryAccess::Word { offset }),
+            Err(MemoryGrowthError::OffsetOutOfRange { offset })
+        );
+        assert_eq!(
+            memory_growth(
+                0,
+                &MemoryAccess::Word {
+                    offset: MAX_OFFSET_TO_DEREF
+                }
+            ),
+            Err(MemoryGrowthError::BeyondAddressableHeap {
+                required_bound: u32::MAX
+            })
+        );
+
+        // the last word of the addressable heap
+        let offset = U256::from(MAX_HEAP_BOUND - 32);
+        assert_eq!(
+            memory_growth(0, &MemoryAccess::Word { offset })
+                .unwrap()
+                .new_bound,
+            MAX_HEAP_BOUND
+        );
+        let offset = offset + U256::one();
+        assert_eq!(
+            memory_growth(0, &MemoryAccess::Word { offset }),
+            Err(MemoryGrowthError::BeyondAddressableHeap {
+                required_bound: MAX_HEAP_BOUND + 1
+            })
+        );
+
+        let pointer = FatPointer {
+            start: MAX_HEAP_BOUND - 10,
+            length: 10,
+            ..FatPointer::empty()
+        };
+        assert!(memory_growth(0, &MemoryAccess::Slice(pointer)).is_ok());
+        let pointer = FatPointer {
+            length: 11,
+            ..pointer
+        };
+        assert_eq!(
+            memory_growth(0, &MemoryAccess::Slice(pointer)),
+            Err(MemoryGrowthError::BeyondAddressableHeap {
+                required_bound: MAX_HEAP_BOUND + 1
+            })
+        );
+
+        let pointer = FatPointer {
+            start: u32::MAX,
+            length: 1,
+            ..FatPointer::empty()
+        };
+        assert!(memory_growth(0, &MemoryAccess::Slice(pointer)).is_err());
+    }
+}
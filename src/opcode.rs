@@ -6,7 +6,15 @@ use crate::{Condition, ImmMemHandlerFlags, NopOpcode, OpcodeVariant, Operand, Re
 use crate::decoding::encoding_mode_production::EncodingModeProduction;
 use crate::definitions::all::Opcode;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "E::PcOrImm: serde::Serialize",
+        deserialize = "E::PcOrImm: serde::Deserialize<'de>"
+    ))
+)]
 pub struct DecodedOpcode<const N: usize = 8, E: VmEncodingMode<N> = EncodingModeProduction> {
     pub variant: OpcodeVariant,
     pub condition: Condition,
@@ -33,6 +41,35 @@ impl<const N: usize, E: VmEncodingMode<N>> Default for DecodedOpcode<N, E> {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpcodeEncodingError {
+    /// Variant is not in the decoding table, so there is no index to encode it with
+    UnknownVariant {
+        variant: OpcodeVariant,
+    },
+    RegisterIndexOutOfRange {
+        index: u8,
+    },
+}
+
+impl core::fmt::Display for OpcodeEncodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OpcodeEncodingError::UnknownVariant { variant } => {
+                write!(f, "{:?} is not encodable", variant)
+            }
+            OpcodeEncodingError::RegisterIndexOutOfRange { index } => write!(
+                f,
+                "register indices must not exceed {}, got {}",
+                REGISTERS_COUNT, index
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OpcodeEncodingError {}
+
 impl<const N: usize, E: VmEncodingMode<N>> DecodedOpcode<N, E> {
     pub fn mask_into_panic(&mut self) {
         // we only mask first 8 bytes of the opcode, and do not touch regs or imms
@@ -68,13 +105,41 @@ impl<const N: usize, E: VmEncodingMode<N>> DecodedOpcode<N, E> {
         E::encode_as_integer(&self)
     }
 
+    /// Same as `serialize_as_integer`, but checks the fields that would be silently
+    /// truncated or encoded into something else
+    pub fn try_serialize_as_integer(
+        &self,
+    ) -> Result<E::IntegerRepresentation, OpcodeEncodingError> {
+        if !OPCODE_TO_CANONICAL_INDEX_LOOKUP_MAP.contains_key(&self.variant) {
+            return Err(OpcodeEncodingError::UnknownVariant {
+                variant: self.variant,
+            });
+        }
+
+        let registers = [
+            self.src0_reg_idx,
+            self.src1_reg_idx,
+            self.dst0_reg_idx,
+            self.dst1_reg_idx,
+        ];
+        // `REGISTERS_COUNT` doesn't include r0
+        if let Some(index) = registers
+            .into_iter()
+            .find(|el| *el as usize > REGISTERS_COUNT)
+        {
+            return Err(OpcodeEncodingError::RegisterIndexOutOfRange { index });
+        }
+
+        Ok(self.serialize_as_integer())
+    }
+
     pub fn serialize_as_bytes(&self) -> [u8; N] {
         E::encode_as_bytes(&self)
     }
 }
 
-impl<const N: usize, E: VmEncodingMode<N>> std::fmt::Display for DecodedOpcode<N, E> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<const N: usize, E: VmEncodingMode<N>> core::fmt::Display for DecodedOpcode<N, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // writeln!(f, "Decoded opcode")?;
         writeln!(f, "{}", self.variant)?;
         writeln!(f, "Condition: {:?}", self.condition)?;
@@ -87,3 +152,52 @@ impl<const N: usize, E: VmEncodingMode<N>> std::fmt::Display for DecodedOpcode<N
         write!(f, "IMM1 constant: {:#08x}", self.imm_1)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AddOpcode, OPCODES_TABLE};
+
+    #[test]
+    fn checked_encoding() {
+        for variant in OPCODES_TABLE.iter() {
+            let decoded: DecodedOpcode = DecodedOpcode {
+                variant: *variant,
+                dst1_reg_idx: REGISTERS_COUNT as u8,
+                imm_0: u16::MAX,
+                ..Default::default()
+            };
+            assert_eq!(
+                decoded.try_serialize_as_integer(),
+                Ok(decoded.serialize_as_integer())
+            );
+        }
+
+        let mut decoded: DecodedOpcode = DecodedOpcode {
+            variant: OPCODES_TABLE[0],
+            ..Default::default()
+        };
+        decoded.src1_reg_idx = REGISTERS_COUNT as u8 + 1;
+        assert_eq!(
+            decoded.try_serialize_as_integer(),
+            Err(OpcodeEncodingError::RegisterIndexOutOfRange {
+                index: REGISTERS_COUNT as u8 + 1
+            })
+        );
+
+        // add has no variant with the swap flag
+        let variant = OpcodeVariant {
+            opcode: Opcode::Add(AddOpcode::Add),
+            flags: [true, true],
+            ..OpcodeVariant::default()
+        };
+        let decoded: DecodedOpcode = DecodedOpcode {
+            variant,
+            ..Default::default()
+        };
+        assert_eq!(
+            decoded.try_serialize_as_integer(),
+            Err(OpcodeEncodingError::UnknownVariant { variant })
+        );
+    }
+}
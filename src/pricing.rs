@@ -0,0 +1,265 @@
+use alloc::vec::Vec;
+
+use crate::circuit_prices::*;
+use crate::system_params::MIN_STORAGE_WRITE_PRICE_FOR_REENTRANCY_PROTECTION;
+use crate::{
+    OpcodeVariant, CALL_LIKE_ERGS_COST, EVENT_IO_PRICE, INVALID_OPCODE_ERGS, L1_MESSAGE_IO_PRICE,
+    STORAGE_READ_IO_PRICE, STORAGE_WRITE_IO_PRICE,
+};
+
+/// All the values that opcode prices are derived from. The default instance is made of the
+/// compile-time constants that the VM uses, any other instance is only meant for simulations
+/// of alternative fee models
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PricingSchedule {
+    pub vm_cycle_cost_in_ergs: u32,
+    pub ram_permutation_cost_in_ergs: u32,
+    pub code_decommitment_cost_per_word_in_ergs: u32,
+    pub log_demuxer_cost_in_ergs: u32,
+    pub storage_sorter_cost_in_ergs: u32,
+    pub events_or_l1_messages_sorter_cost_in_ergs: u32,
+    pub code_decommitment_sorter_cost_in_ergs: u32,
+    pub l1_message_min_cost_in_ergs: u32,
+    pub storage_write_hasher_min_cost_in_ergs: u32,
+    pub min_storage_write_price_for_reentrancy_protection: u32,
+    pub storage_read_io_price: u32,
+    pub storage_write_io_price: u32,
+    pub event_io_price: u32,
+    pub l1_message_io_price: u32,
+    pub call_like_ergs_cost: u32,
+    pub invalid_opcode_ergs: u32,
+}
+
+pub const DEFAULT_PRICING_SCHEDULE: PricingSchedule = PricingSchedule {
+    vm_cycle_cost_in_ergs: VM_CYCLE_COST_IN_ERGS,
+    ram_permutation_cost_in_ergs: RAM_PERMUTATION_COST_IN_ERGS,
+    code_decommitment_cost_per_word_in_ergs: CODE_DECOMMITMENT_COST_PER_WORD_IN_ERGS,
+    log_demuxer_cost_in_ergs: LOG_DEMUXER_COST_IN_ERGS,
+    storage_sorter_cost_in_ergs: STORAGE_SORTER_COST_IN_ERGS,
+    events_or_l1_messages_sorter_cost_in_ergs: EVENTS_OR_L1_MESSAGES_SORTER_COST_IN_ERGS,
+    code_decommitment_sorter_cost_in_ergs: CODE_DECOMMITMENT_SORTER_COST_IN_ERGS,
+    l1_message_min_cost_in_ergs: L1_MESSAGE_MIN_COST_IN_ERGS,
+    storage_write_hasher_min_cost_in_ergs: STORAGE_WRITE_HASHER_MIN_COST_IN_ERGS,
+    min_storage_write_price_for_reentrancy_protection:
+        MIN_STORAGE_WRITE_PRICE_FOR_REENTRANCY_PROTECTION,
+    storage_read_io_price: STORAGE_READ_IO_PRICE,
+    storage_write_io_price: STORAGE_WRITE_IO_PRICE,
+    event_io_price: EVENT_IO_PRICE,
+    l1_message_io_price: L1_MESSAGE_IO_PRICE,
+    call_like_ergs_cost: CALL_LIKE_ERGS_COST,
+    invalid_opcode_ergs: INVALID_OPCODE_ERGS,
+};
+
+impl Default for PricingSchedule {
+    fn default() -> Self {
+        DEFAULT_PRICING_SCHEDULE
+    }
+}
+
+impl PricingSchedule {
+    /// Same as `RICH_ADDRESSING_OPCODE_ERGS`
+    pub const fn rich_addressing_opcode_ergs(&self) -> u32 {
+        self.rich_addressing_opcode_breakdown().total()
+    }
+
+    /// Same as `AVERAGE_OPCODE_ERGS`
+    pub const fn average_opcode_ergs(&self) -> u32 {
+        self.average_opcode_breakdown().total()
+    }
+
+    pub const fn rich_addressing_opcode_breakdown(&self) -> PriceBreakdown {
+        PriceBreakdown {
+            vm_cycles: self.vm_cycle_cost_in_ergs,
+            ram_permutations: self.ram_permutation_cost_in_ergs.saturating_mul(2),
+            log_demuxer: 0,
+            sorters: 0,
+            io: 0,
+            call_like: 0,
+            min_cost_floor: 0,
+            invalid_opcode: 0,
+        }
+    }
+
+    pub const fn average_opcode_breakdown(&self) -> PriceBreakdown {
+        PriceBreakdown {
+            vm_cycles: self.vm_cycle_cost_in_ergs,
+            ram_permutations: self.ram_permutation_cost_in_ergs,
+            log_demuxer: 0,
+            sorters: 0,
+            io: 0,
+            call_like: 0,
+            min_cost_floor: 0,
+            invalid_opcode: 0,
+        }
+    }
+
+    /// Same as `MIN_STORAGE_WRITE_COST`
+    pub const fn min_storage_write_cost(&self) -> u32 {
+        if self.min_storage_write_price_for_reentrancy_protection
+            > self.storage_write_hasher_min_cost_in_ergs
+        {
+            self.min_storage_write_price_for_reentrancy_protection
+        } else {
+            self.storage_write_hasher_min_cost_in_ergs
+        }
+    }
+}
+
+/// The ergs price of an opcode split by what it is paid for. Components always sum to the price,
+/// unless the price saturates
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PriceBreakdown {
+    pub vm_cycles: u32,
+    pub ram_permutations: u32,
+    pub log_demuxer: u32,
+    pub sorters: u32,
+    pub io: u32,
+    pub call_like: u32,
+    /// Paid on top of the intrinsic cost to reach the DDoS/reentrancy protection minimum
+    pub min_cost_floor: u32,
+    /// Invalid opcode burns everything at once
+    pub invalid_opcode: u32,
+}
+
+impl PriceBreakdown {
+    /// Saturates at `u32::MAX`, that is more than any transaction can pay, as custom
+    /// schedules may have arbitrary large prices
+    pub const fn total(&self) -> u32 {
+        self.vm_cycles
+            .saturating_add(self.ram_permutations)
+            .saturating_add(self.log_demuxer)
+            .saturating_add(self.sorters)
+            .saturating_add(self.io)
+            .saturating_add(self.call_like)
+            .saturating_add(self.min_cost_floor)
+            .saturating_add(self.invalid_opcode)
+    }
+
+    pub fn components(&self) -> [(&'static str, u32); 8] {
+        [
+            ("vm cycles", self.vm_cycles),
+            ("ram permutations", self.ram_permutations),
+            ("log demuxer", self.log_demuxer),
+            ("sorters", self.sorters),
+            ("io", self.io),
+            ("call like", self.call_like),
+            ("min cost floor", self.min_cost_floor),
+            ("invalid opcode", self.invalid_opcode),
+        ]
+    }
+
+    /// Raises the price up to `min_cost` if the intrinsic cost is lower
+    pub const fn with_min_cost(mut self, min_cost: u32) -> Self {
+        let intrinsic = self.total();
+        if intrinsic < min_cost {
+            self.min_cost_floor += min_cost - intrinsic;
+        }
+
+        self
+    }
+}
+
+impl core::fmt::Display for PriceBreakdown {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.total())?;
+        let mut separator = " = ";
+        for (name, value) in self.components() {
+            if value != 0 {
+                write!(f, "{}{} ({})", separator, value, name)?;
+                separator = " + ";
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Prices for every entry of the decoding table under the given schedule
+pub fn synthesize_opcodes_prices(table: &[OpcodeVariant], schedule: &PricingSchedule) -> Vec<u32> {
+    table
+        .iter()
+        .map(|el| el.ergs_price_with(schedule))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::system_params::MIN_STORAGE_WRITE_COST;
+    use crate::{
+        LogOpcode, Opcode, AVERAGE_OPCODE_ERGS, OPCODES_PRICES, OPCODES_TABLE,
+        RICH_ADDRESSING_OPCODE_ERGS,
+    };
+
+    #[test]
+    fn default_schedule_matches_constants() {
+        let schedule = PricingSchedule::default();
+        assert_eq!(
+            schedule.rich_addressing_opcode_ergs(),
+            RICH_ADDRESSING_OPCODE_ERGS
+        );
+        assert_eq!(schedule.average_opcode_ergs(), AVERAGE_OPCODE_ERGS);
+        assert_eq!(schedule.min_storage_write_cost(), MIN_STORAGE_WRITE_COST);
+
+        let prices = synthesize_opcodes_prices(&*OPCODES_TABLE, &schedule);
+        assert_eq!(&prices[..], &OPCODES_PRICES[..]);
+    }
+
+    #[test]
+    fn alternative_schedule_reprices_opcodes() {
+        let schedule = PricingSchedule {
+            vm_cycle_cost_in_ergs: 2 * VM_CYCLE_COST_IN_ERGS,
+            ..PricingSchedule::default()
+        };
+        let nop = *crate::NOP_OPCODE_VARIANT;
+        assert_eq!(
+            nop.ergs_price_with(&schedule),
+            nop.ergs_price() + VM_CYCLE_COST_IN_ERGS
+        );
+    }
+
+    #[test]
+    fn price_breakdown_sums_to_price() {
+        for variant in OPCODES_TABLE.iter() {
+            let breakdown = variant.price_breakdown();
+            let sum = breakdown
+                .components()
+                .iter()
+                .fold(0u64, |acc, (_, value)| acc + *value as u64);
+            assert_eq!(
+                sum,
+                variant.ergs_price() as u64,
+                "breakdown {} of {:?} does not match the price",
+                breakdown,
+                variant.opcode
+            );
+        }
+    }
+
+    #[test]
+    fn prices_saturate() {
+        let schedule = PricingSchedule {
+            vm_cycle_cost_in_ergs: u32::MAX,
+            storage_sorter_cost_in_ergs: u32::MAX,
+            code_decommitment_sorter_cost_in_ergs: 1,
+            ..PricingSchedule::default()
+        };
+        assert_eq!(schedule.rich_addressing_opcode_ergs(), u32::MAX);
+
+        let prices = synthesize_opcodes_prices(&*OPCODES_TABLE, &schedule);
+        assert!(prices.iter().all(|el| *el == u32::MAX));
+
+        let breakdown =
+            Opcode::FarCall(crate::FarCallOpcode::Normal).price_breakdown_with(&schedule);
+        assert_eq!(breakdown.vm_cycles, u32::MAX);
+        assert_eq!(breakdown.sorters, u32::MAX);
+        assert_eq!(breakdown.total(), u32::MAX);
+    }
+
+    #[test]
+    fn storage_write_breakdown_includes_min_cost_floor() {
+        let breakdown = Opcode::Log(LogOpcode::StorageWrite).price_breakdown();
+        assert!(breakdown.min_cost_floor > 0);
+        assert_eq!(breakdown.total(), MIN_STORAGE_WRITE_COST);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::system_params::{
+    BYTECODE_PUBLICATION_LENGTH_PREFIX_BYTES, INITIAL_STORAGE_WRITE_PUBDATA_BYTES,
+    L1_MESSAGE_PUBDATA_BYTES, MAX_PUBDATA_PER_BLOCK, MSG_VALUE_SIMULATOR_PUBDATA_BYTES_TO_PREPAY,
+    REPEATED_STORAGE_WRITE_PUBDATA_BYTES,
+};
+use crate::VmMetaParameters;
+
+/// Counts the public data that has to be sent to L1
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PubdataAccumulator {
+    pub initial_storage_writes: u64,
+    pub repeated_storage_writes: u64,
+    pub l1_messages: u64,
+    pub published_bytecodes: u64,
+    pub published_bytecode_bytes: u64,
+    pub msg_value_simulator_prepayments: u64,
+}
+
+impl PubdataAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_initial_storage_write(&mut self) {
+        self.initial_storage_writes += 1;
+    }
+
+    pub fn add_repeated_storage_write(&mut self) {
+        self.repeated_storage_writes += 1;
+    }
+
+    pub fn add_l1_message(&mut self) {
+        self.l1_messages += 1;
+    }
+
+    pub fn add_published_bytecode(&mut self, bytecode_len_in_bytes: usize) {
+        self.published_bytecodes += 1;
+        self.published_bytecode_bytes += bytecode_len_in_bytes as u64;
+    }
+
+    pub fn add_msg_value_simulator_prepayment(&mut self) {
+        self.msg_value_simulator_prepayments += 1;
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.initial_storage_writes += other.initial_storage_writes;
+        self.repeated_storage_writes += other.repeated_storage_writes;
+        self.l1_messages += other.l1_messages;
+        self.published_bytecodes += other.published_bytecodes;
+        self.published_bytecode_bytes += other.published_bytecode_bytes;
+        self.msg_value_simulator_prepayments += other.msg_value_simulator_prepayments;
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.initial_storage_writes * INITIAL_STORAGE_WRITE_PUBDATA_BYTES as u64
+            + self.repeated_storage_writes * REPEATED_STORAGE_WRITE_PUBDATA_BYTES as u64
+            + self.l1_messages * L1_MESSAGE_PUBDATA_BYTES as u64
+            + self.published_bytecodes * BYTECODE_PUBLICATION_LENGTH_PREFIX_BYTES as u64
+            + self.published_bytecode_bytes
+            + self.msg_value_simulator_prepayments
+                * MSG_VALUE_SIMULATOR_PUBDATA_BYTES_TO_PREPAY as u64
+    }
+
+    /// Returns `None` if the price does not fit into `u32`
+    pub fn ergs_to_charge(&self, ergs_per_pubdata_byte: u32) -> Option<u32> {
+        let ergs = self
+            .total_bytes()
+            .checked_mul(ergs_per_pubdata_byte as u64)?;
+
+        u32::try_from(ergs).ok()
+    }
+
+    pub fn ergs_to_charge_for_meta(&self, meta: &VmMetaParameters) -> Option<u32> {
+        self.ergs_to_charge(meta.ergs_per_pubdata_byte)
+    }
+
+    pub fn exceeds_max_pubdata_per_block(&self) -> bool {
+        self.total_bytes() > MAX_PUBDATA_PER_BLOCK as u64
+    }
+
+    pub fn remaining_bytes_in_block(&self) -> u64 {
+        (MAX_PUBDATA_PER_BLOCK as u64).saturating_sub(self.total_bytes())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accumulate_pubdata() {
+        let mut accumulator = PubdataAccumulator::new();
+        accumulator.add_initial_storage_write();
+        accumulator.add_repeated_storage_write();
+        accumulator.add_l1_message();
+        accumulator.add_published_bytecode(32 * 3);
+
+        let expected_bytes = 64 + 40 + 88 + 4 + 96;
+        assert_eq!(accumulator.total_bytes(), expected_bytes);
+        assert_eq!(
+            accumulator.ergs_to_charge(10),
+            Some(expected_bytes as u32 * 10)
+        );
+        assert_eq!(accumulator.ergs_to_charge(u32::MAX), None);
+        assert!(!accumulator.exceeds_max_pubdata_per_block());
+
+        let mut block = PubdataAccumulator::new();
+        for _ in 0..(MAX_PUBDATA_PER_BLOCK as u64 / expected_bytes + 1) {
+            block.merge(&accumulator);
+        }
+        assert!(block.exceeds_max_pubdata_per_block());
+        assert_eq!(block.remaining_bytes_in_block(), 0);
+    }
+
+    #[test]
+    fn published_bytecodes() {
+        // every bytecode costs its length plus the 4 byte length prefix
+        let mut accumulator = PubdataAccumulator::new();
+        accumulator.add_published_bytecode(32);
+        accumulator.add_published_bytecode(32 * 5);
+        assert_eq!(accumulator.total_bytes(), 200);
+        assert_eq!(accumulator.ergs_to_charge(17), Some(3400));
+
+        // the largest bytecode is (2^16 - 1) words long
+        let mut accumulator = PubdataAccumulator::new();
+        accumulator.add_published_bytecode(32 * (u16::MAX as usize));
+        assert_eq!(accumulator.total_bytes(), 2_097_124);
+        assert!(accumulator.exceeds_max_pubdata_per_block());
+    }
+}
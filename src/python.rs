@@ -0,0 +1,496 @@
+// Python module for trace analysis, built with `maturin develop` from the repository root
+// (see `pyproject.toml`). Instructions use the production encoding, ABI words are
+// exchanged as Python integers and bytecode as `bytes`.
+// Tests are in `python/tests` and are run with `python -m unittest discover python/tests`
+
+use ethereum_types::U256;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyInt};
+
+use crate::decoding::{EncodingModeProduction, VmEncodingMode};
+use crate::{
+    Condition, DecodedOpcode, FarCallABI, FatPointer, OpcodeVariant, RetABI, ALL_CONDITIONS,
+    OPCODES_TABLE,
+};
+
+const INSTRUCTION_BYTES: usize = 8;
+
+fn u256_from_py(value: &Bound<'_, PyAny>) -> PyResult<U256> {
+    // `int.to_bytes` raises `OverflowError` for negative and too large values
+    let bytes = value.call_method1("to_bytes", (32, "big"))?;
+
+    Ok(U256::from_big_endian(
+        bytes.downcast::<PyBytes>()?.as_bytes(),
+    ))
+}
+
+fn u256_to_py(py: Python<'_>, value: U256) -> PyResult<PyObject> {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    let result = py
+        .get_type::<PyInt>()
+        .call_method1("from_bytes", (PyBytes::new(py, &bytes), "big"))?;
+
+    Ok(result.unbind())
+}
+
+fn condition_from_name(name: &str) -> PyResult<Condition> {
+    ALL_CONDITIONS
+        .iter()
+        .copied()
+        .find(|el| format!("{:?}", el) == name)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown condition `{}`", name)))
+}
+
+#[pyclass(name = "OpcodeVariant", module = "zkevm_opcode_defs", frozen)]
+#[derive(Clone)]
+pub struct PyOpcodeVariant(pub OpcodeVariant);
+
+#[pymethods]
+impl PyOpcodeVariant {
+    /// Variants of the decoding table of the default ISA version, in the encoding order
+    #[staticmethod]
+    fn table() -> Vec<Self> {
+        OPCODES_TABLE.iter().copied().map(PyOpcodeVariant).collect()
+    }
+
+    /// Mnemonic such as `Log.StorageWrite`
+    #[getter]
+    fn opcode(&self) -> String {
+        self.0.opcode.mnemonic()
+    }
+
+    #[getter]
+    fn src0_operand(&self) -> String {
+        format!("{:?}", self.0.src0_operand_type)
+    }
+
+    #[getter]
+    fn dst0_operand(&self) -> String {
+        format!("{:?}", self.0.dst0_operand_type)
+    }
+
+    #[getter]
+    fn flags(&self) -> Vec<bool> {
+        self.0.flags.to_vec()
+    }
+
+    #[getter]
+    fn ergs_price(&self) -> u32 {
+        self.0.ergs_price()
+    }
+
+    #[getter]
+    fn requires_kernel_mode(&self) -> bool {
+        self.0.requires_kernel_mode()
+    }
+
+    #[getter]
+    fn can_be_used_in_static_context(&self) -> bool {
+        self.0.can_be_used_in_static_context()
+    }
+
+    #[getter]
+    fn is_explicit_panic(&self) -> bool {
+        self.0.is_explicit_panic()
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("OpcodeVariant({:?})", self.0)
+    }
+}
+
+#[pyclass(name = "DecodedOpcode", module = "zkevm_opcode_defs")]
+#[derive(Clone)]
+pub struct PyDecodedOpcode(pub DecodedOpcode);
+
+#[pymethods]
+impl PyDecodedOpcode {
+    #[new]
+    #[pyo3(signature = (variant, condition = "Always", src0_reg_idx = 0, src1_reg_idx = 0, dst0_reg_idx = 0, dst1_reg_idx = 0, imm_0 = 0, imm_1 = 0))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        variant: PyOpcodeVariant,
+        condition: &str,
+        src0_reg_idx: u8,
+        src1_reg_idx: u8,
+        dst0_reg_idx: u8,
+        dst1_reg_idx: u8,
+        imm_0: u16,
+        imm_1: u16,
+    ) -> PyResult<Self> {
+        Ok(PyDecodedOpcode(DecodedOpcode {
+            variant: variant.0,
+            condition: condition_from_name(condition)?,
+            src0_reg_idx,
+            src1_reg_idx,
+            dst0_reg_idx,
+            dst1_reg_idx,
+            imm_0,
+            imm_1,
+        }))
+    }
+
+    /// Any integer is decodable, use `is_canonical_encoding` to filter out the ones
+    /// that the VM would reject
+    #[staticmethod]
+    fn decode(raw: u64) -> Self {
+        let (decoded, _) =
+            EncodingModeProduction::parse_preliminary_variant_and_absolute_number(raw);
+
+        PyDecodedOpcode(decoded)
+    }
+
+    /// Decodes 8 bytes in the order they are stored in the bytecode
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        let bytes: [u8; INSTRUCTION_BYTES] = bytes.try_into().map_err(|_| {
+            PyValueError::new_err(format!(
+                "instruction must be {} bytes long, got {}",
+                INSTRUCTION_BYTES,
+                bytes.len()
+            ))
+        })?;
+
+        Ok(Self::decode(u64::from_be_bytes(bytes)))
+    }
+
+    fn encode(&self) -> PyResult<u64> {
+        self.0
+            .try_serialize_as_integer()
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let raw = self.encode()?;
+
+        Ok(PyBytes::new(py, &raw.to_be_bytes()))
+    }
+
+    #[getter]
+    fn variant(&self) -> PyOpcodeVariant {
+        PyOpcodeVariant(self.0.variant)
+    }
+
+    #[setter]
+    fn set_variant(&mut self, variant: PyOpcodeVariant) {
+        self.0.variant = variant.0;
+    }
+
+    #[getter]
+    fn condition(&self) -> String {
+        format!("{:?}", self.0.condition)
+    }
+
+    #[setter]
+    fn set_condition(&mut self, condition: &str) -> PyResult<()> {
+        self.0.condition = condition_from_name(condition)?;
+
+        Ok(())
+    }
+
+    #[getter]
+    fn src0_reg_idx(&self) -> u8 {
+        self.0.src0_reg_idx
+    }
+
+    #[setter]
+    fn set_src0_reg_idx(&mut self, value: u8) {
+        self.0.src0_reg_idx = value;
+    }
+
+    #[getter]
+    fn src1_reg_idx(&self) -> u8 {
+        self.0.src1_reg_idx
+    }
+
+    #[setter]
+    fn set_src1_reg_idx(&mut self, value: u8) {
+        self.0.src1_reg_idx = value;
+    }
+
+    #[getter]
+    fn dst0_reg_idx(&self) -> u8 {
+        self.0.dst0_reg_idx
+    }
+
+    #[setter]
+    fn set_dst0_reg_idx(&mut self, value: u8) {
+        self.0.dst0_reg_idx = value;
+    }
+
+    #[getter]
+    fn dst1_reg_idx(&self) -> u8 {
+        self.0.dst1_reg_idx
+    }
+
+    #[setter]
+    fn set_dst1_reg_idx(&mut self, value: u8) {
+        self.0.dst1_reg_idx = value;
+    }
+
+    #[getter]
+    fn imm_0(&self) -> u16 {
+        self.0.imm_0
+    }
+
+    #[setter]
+    fn set_imm_0(&mut self, value: u16) {
+        self.0.imm_0 = value;
+    }
+
+    #[getter]
+    fn imm_1(&self) -> u16 {
+        self.0.imm_1
+    }
+
+    #[setter]
+    fn set_imm_1(&mut self, value: u16) {
+        self.0.imm_1 = value;
+    }
+
+    /// Same text as the disassembler prints
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("DecodedOpcode({:?})", self.0)
+    }
+}
+
+#[pyclass(name = "FatPointer", module = "zkevm_opcode_defs")]
+#[derive(Clone)]
+pub struct PyFatPointer(pub FatPointer);
+
+#[pymethods]
+impl PyFatPointer {
+    #[new]
+    #[pyo3(signature = (offset = 0, memory_page = 0, start = 0, length = 0))]
+    fn new(offset: u32, memory_page: u32, start: u32, length: u32) -> Self {
+        PyFatPointer(FatPointer {
+            offset,
+            memory_page,
+            start,
+            length,
+        })
+    }
+
+    #[staticmethod]
+    fn from_int(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(PyFatPointer(FatPointer::from_u256(u256_from_py(value)?)))
+    }
+
+    fn to_int(&self, py: Python<'_>) -> PyResult<PyObject> {
+        u256_to_py(py, self.0.to_u256())
+    }
+
+    #[getter]
+    fn offset(&self) -> u32 {
+        self.0.offset
+    }
+
+    #[getter]
+    fn memory_page(&self) -> u32 {
+        self.0.memory_page
+    }
+
+    #[getter]
+    fn start(&self) -> u32 {
+        self.0.start
+    }
+
+    #[getter]
+    fn length(&self) -> u32 {
+        self.0.length
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[pyclass(name = "FarCallABI", module = "zkevm_opcode_defs", frozen)]
+#[derive(Clone)]
+pub struct PyFarCallABI(pub FarCallABI);
+
+#[pymethods]
+impl PyFarCallABI {
+    #[staticmethod]
+    fn from_int(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(PyFarCallABI(FarCallABI::from_u256(u256_from_py(value)?)))
+    }
+
+    #[getter]
+    fn memory_quasi_fat_pointer(&self) -> PyFatPointer {
+        PyFatPointer(self.0.memory_quasi_fat_pointer)
+    }
+
+    #[getter]
+    fn ergs_passed(&self) -> u32 {
+        self.0.ergs_passed
+    }
+
+    #[getter]
+    fn shard_id(&self) -> u8 {
+        self.0.shard_id
+    }
+
+    #[getter]
+    fn forwarding_mode(&self) -> String {
+        format!("{:?}", self.0.forwarding_mode)
+    }
+
+    #[getter]
+    fn constructor_call(&self) -> bool {
+        self.0.constructor_call
+    }
+
+    #[getter]
+    fn to_system(&self) -> bool {
+        self.0.to_system
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[pyclass(name = "RetABI", module = "zkevm_opcode_defs", frozen)]
+#[derive(Clone)]
+pub struct PyRetABI(pub RetABI);
+
+#[pymethods]
+impl PyRetABI {
+    #[staticmethod]
+    fn from_int(value: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(PyRetABI(RetABI::from_u256(u256_from_py(value)?)))
+    }
+
+    #[getter]
+    fn memory_quasi_fat_pointer(&self) -> PyFatPointer {
+        PyFatPointer(self.0.memory_quasi_fat_pointer)
+    }
+
+    #[getter]
+    fn page_forwarding_mode(&self) -> String {
+        format!("{:?}", self.0.page_forwarding_mode)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+#[pyfunction]
+fn is_canonical_encoding(raw: u64) -> bool {
+    EncodingModeProduction::is_canonical_encoding(raw)
+}
+
+/// Decodes every instruction of the bytecode, including the ones in the data section
+#[pyfunction]
+fn decode_bytecode(bytecode: &[u8]) -> PyResult<Vec<PyDecodedOpcode>> {
+    let instructions =
+        crate::decode_bytecode(bytecode).map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+    Ok(instructions
+        .into_iter()
+        .map(|(_, decoded)| PyDecodedOpcode(decoded))
+        .collect())
+}
+
+#[pyfunction]
+fn bytecode_to_code_hash<'py>(py: Python<'py>, bytecode: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    let hash = crate::bytecode_to_words(bytecode)
+        .and_then(|words| crate::bytecode_to_code_hash(&words))
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+    Ok(PyBytes::new(py, &hash))
+}
+
+#[pymodule]
+fn zkevm_opcode_defs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyOpcodeVariant>()?;
+    m.add_class::<PyDecodedOpcode>()?;
+    m.add_class::<PyFatPointer>()?;
+    m.add_class::<PyFarCallABI>()?;
+    m.add_class::<PyRetABI>()?;
+    m.add_function(wrap_pyfunction!(is_canonical_encoding, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_bytecode, m)?)?;
+    m.add_function(wrap_pyfunction!(bytecode_to_code_hash, m)?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Opcode, RetOpcode};
+
+    #[test]
+    fn encode_decode() {
+        let panic = EncodingModeProduction::exception_revert_encoding();
+        let decoded = PyDecodedOpcode::decode(panic);
+        assert_eq!(decoded.0.variant.opcode, Opcode::Ret(RetOpcode::Panic));
+        assert_eq!(decoded.encode().unwrap(), panic);
+
+        let mut decoded = PyDecodedOpcode::from_bytes(&panic.to_be_bytes()).unwrap();
+        decoded.set_dst1_reg_idx(16);
+        assert!(decoded.encode().is_err());
+        assert!(decoded.set_condition("Never").is_err());
+        assert!(PyDecodedOpcode::from_bytes(&[0u8; 7]).is_err());
+    }
+
+    #[test]
+    fn bytecode() {
+        let nop = EncodingModeProduction::nop_encoding().to_be_bytes();
+        let panic = EncodingModeProduction::exception_revert_encoding().to_be_bytes();
+        let word: Vec<u8> = [nop, nop, nop, panic].concat();
+
+        let instructions = decode_bytecode(&word).unwrap();
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(
+            instructions[3].0.variant.opcode,
+            Opcode::Ret(RetOpcode::Panic)
+        );
+        assert!(decode_bytecode(&word[..16]).is_err());
+        assert!(decode_bytecode(&[]).unwrap().is_empty());
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let hash = bytecode_to_code_hash(py, &word).unwrap();
+            let expected = crate::bytecode_to_code_hash(&[word.clone().try_into().unwrap()]);
+            assert_eq!(hash.as_bytes(), &expected.unwrap()[..]);
+
+            assert!(bytecode_to_code_hash(py, &word[..16]).is_err());
+            assert!(bytecode_to_code_hash(py, &[word.clone(), word].concat()).is_err());
+        });
+    }
+
+    #[test]
+    fn abi_ints() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let pointer = PyFatPointer::new(1, 2, 3, 4);
+            let value = pointer.to_int(py).unwrap();
+            let parsed = PyFatPointer::from_int(value.bind(py)).unwrap();
+            assert_eq!(parsed.0, pointer.0);
+
+            let too_large = py.eval(pyo3::ffi::c_str!("1 << 256"), None, None).unwrap();
+            assert!(PyFarCallABI::from_int(&too_large).is_err());
+        });
+    }
+}
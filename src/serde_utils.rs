@@ -0,0 +1,182 @@
+// Byte arrays are stored as `0x` prefixed lowercase hex strings
+pub(crate) mod hex_bytes {
+    use alloc::format;
+    use alloc::string::String;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&crate::utils::bytes_to_hex(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        crate::utils::bytes_from_hex(&encoded)
+            .and_then(|el| el.try_into().ok())
+            .ok_or_else(|| {
+                D::Error::custom(format!(
+                    "expected {} hex encoded bytes, got `{}`",
+                    N, encoded
+                ))
+            })
+    }
+}
+
+// ABI words are stored as `0x` prefixed hex integers, the same as the VM sees them
+macro_rules! serde_as_u256 {
+    ($ty:ty) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.to_u256().serialize(serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                ethereum_types::U256::deserialize(deserializer).map(Self::from_u256)
+            }
+        }
+    };
+}
+
+pub(crate) use serde_as_u256;
+
+#[cfg(test)]
+mod test {
+    use crate::decoding::{EncodingModeProduction, EncodingModeTesting, VmEncodingMode};
+    use crate::*;
+    use ethereum_types::U256;
+
+    fn round_trip<T>(value: &T) -> String
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + core::fmt::Debug,
+    {
+        let encoded = serde_json::to_string(value).unwrap();
+        let decoded: T = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(&decoded, value, "round trip through {}", encoded);
+
+        encoded
+    }
+
+    #[test]
+    fn opcodes_round_trip() {
+        for opcode in Opcode::all_variants() {
+            round_trip(&opcode);
+        }
+        for variant in OPCODES_TABLE.iter() {
+            round_trip(variant);
+        }
+        for condition in ALL_CONDITIONS.iter() {
+            round_trip(condition);
+        }
+
+        assert_eq!(
+            round_trip(&Opcode::UMA(UMAOpcode::HeapWrite)),
+            "\"UMA.HeapWrite\""
+        );
+        assert_eq!(round_trip(&LogOpcode::Event), "\"Event\"");
+        assert_eq!(round_trip(&Condition::GtOrLt), "\"GtOrLt\"");
+        assert_eq!(
+            round_trip(&Operand::Full(ImmMemHandlerFlags::UseCodePage)),
+            "{\"Full\":\"UseCodePage\"}"
+        );
+        assert!(serde_json::from_str::<Opcode>("\"Log.Unknown\"").is_err());
+    }
+
+    fn check_decoded_opcodes<const N: usize, E: VmEncodingMode<N>>(raw: &[E::IntegerRepresentation])
+    where
+        E::PcOrImm: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        for el in raw.iter() {
+            let (opcode, _) = E::parse_preliminary_variant_and_absolute_number(*el);
+            let encoded = serde_json::to_string(&opcode).unwrap();
+            let decoded: DecodedOpcode<N, E> = serde_json::from_str(&encoded).unwrap();
+            assert_eq!(
+                decoded.serialize_as_integer(),
+                opcode.serialize_as_integer()
+            );
+        }
+    }
+
+    #[test]
+    fn decoded_opcodes_round_trip() {
+        check_decoded_opcodes::<8, EncodingModeProduction>(&[
+            EncodingModeProduction::nop_encoding(),
+            EncodingModeProduction::exception_revert_encoding(),
+            0x0001_0002_0003_0139,
+        ]);
+        check_decoded_opcodes::<16, EncodingModeTesting>(&[
+            EncodingModeTesting::nop_encoding(),
+            EncodingModeTesting::exception_revert_encoding(),
+        ]);
+    }
+
+    #[test]
+    fn abi_round_trip() {
+        let raw = U256::from_dec_str(
+            "452319300877325313852488925888724764263521004047156906617735320131041551860",
+        )
+        .unwrap();
+
+        let pointer = FatPointer::from_u256(raw);
+        assert_eq!(
+            round_trip(&pointer),
+            format!("\"{:#x}\"", pointer.to_u256())
+        );
+        let far_call = FarCallABI::from_u256(raw);
+        assert_eq!(
+            round_trip(&far_call),
+            format!("\"{:#x}\"", far_call.to_u256())
+        );
+        let ret = RetABI::from_u256(raw);
+        assert_eq!(round_trip(&ret), format!("\"{:#x}\"", ret.to_u256()));
+        assert_eq!(round_trip(&FatPointer::empty()), "\"0x0\"");
+        assert!(serde_json::from_str::<FatPointer>("{\"offset\":0}").is_err());
+
+        let precompile_call = PrecompileCallABI::from_u256(raw);
+        assert_eq!(
+            round_trip(&precompile_call),
+            format!("\"{:#x}\"", precompile_call.to_u256())
+        );
+        assert_eq!(
+            PrecompileCallABI::from_u256(precompile_call.to_u256()),
+            precompile_call
+        );
+        round_trip(&VmMetaParameters {
+            ergs_per_pubdata_byte: 17,
+            heap_size: 1 << 20,
+            aux_heap_size: 1024,
+            this_shard_id: 0,
+            caller_shard_id: 1,
+            code_shard_id: 0,
+        });
+    }
+
+    #[test]
+    fn code_hash_round_trip() {
+        let mut digest = [0u8; 32];
+        digest[4] = 0xab;
+        digest[31] = 0x01;
+        let hash = VersionedHashGeneric::<ContractCodeSha256>::from_digest_and_preimage_num_words(
+            digest, 3,
+        );
+        let encoded = round_trip(hash.layout_ref());
+        assert_eq!(
+            encoded,
+            format!(
+                "{{\"code_length_in_words\":3,\"extra_marker\":0,\"partial_hash\":\"0xab{}01\"}}",
+                "00".repeat(26)
+            )
+        );
+
+        let signed = "{\"code_length_in_words\":3,\"extra_marker\":0,\"partial_hash\":\"0x+1\"}";
+        assert!(serde_json::from_str::<ContractCodeSha256Storage>(signed).is_err());
+
+        let too_short = "{\"code_length_in_words\":3,\"extra_marker\":0,\"partial_hash\":\"0xab\"}";
+        assert!(serde_json::from_str::<ContractCodeSha256Storage>(too_short).is_err());
+    }
+}
@@ -26,6 +26,9 @@ pub const ECRECOVER_INNER_FUNCTION_PRECOMPILE_ADDRESS: u16 = 0x01; // as in Ethe
 pub const INITIAL_STORAGE_WRITE_PUBDATA_BYTES: usize = 64;
 pub const REPEATED_STORAGE_WRITE_PUBDATA_BYTES: usize = 40;
 pub const L1_MESSAGE_PUBDATA_BYTES: u32 = 1 + 1 + 2 + 20 + 32 + 32;
+/// Published bytecodes are prefixed with their length as 4 bytes, as `L1Messenger.requestBytecodeL1Publication`
+/// of the system contracts charges `4 + _bytecode.length` bytes of pubdata for them
+pub const BYTECODE_PUBLICATION_LENGTH_PREFIX_BYTES: u32 = 4;
 
 /// The maximal amount of public data in bytes that could be sent within an L1 batch
 /// The limit that the Geth nodes impose is 128kb.
@@ -82,7 +85,7 @@ pub const MSG_VALUE_SIMULATOR_ADDITIVE_COST: u32 =
 /// the user spends less funds, only the parent frame will receivet the refund)
 pub const MSG_VALUE_SIMULATOR_MIN_USED_ERGS: u32 = 8000 + DECOMMITMENT_MSG_VALUE_SIMULATOR_OVERHEAD;
 
-// std::cmp::max is not yet stabilized as const fn yet
+// core::cmp::max is not yet stabilized as const fn yet
 const fn max(a: u32, b: u32) -> u32 {
     if a > b {
         a
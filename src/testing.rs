@@ -0,0 +1,148 @@
+// Proptest strategies for the opcode encodings, exported for the tests of downstream crates
+
+use alloc::vec::Vec;
+
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::decoding::{EncodingModeProduction, EncodingModeTesting, VmEncodingMode};
+use crate::{
+    Condition, DecodedOpcode, OpcodeVariant, ALL_CONDITIONS, CONDITIONAL_BITS_SHIFT, OPCODES_TABLE,
+    OPCODE_TO_CANONICAL_INDEX_LOOKUP_MAP, REGISTERS_COUNT,
+};
+
+/// Positions in `OPCODES_TABLE` that variants are encoded with. The rest of the table is
+/// padding with duplicates, that decode fine but are encoded with the index of the first copy
+pub fn canonical_variant_indices() -> Vec<usize> {
+    OPCODES_TABLE
+        .iter()
+        .enumerate()
+        .filter(|(idx, el)| OPCODE_TO_CANONICAL_INDEX_LOOKUP_MAP[*el] == *idx)
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Any variant of the default ISA version
+pub fn arb_opcode_variant() -> impl Strategy<Value = OpcodeVariant> {
+    let variants: Vec<_> = canonical_variant_indices()
+        .into_iter()
+        .map(|el| OPCODES_TABLE[el])
+        .collect();
+
+    select(variants)
+}
+
+pub fn arb_condition() -> impl Strategy<Value = Condition> {
+    select(ALL_CONDITIONS.to_vec())
+}
+
+pub fn arb_register_index() -> impl Strategy<Value = u8> {
+    0..=(REGISTERS_COUNT as u8)
+}
+
+/// Instructions that are encodable in the mode, with any registers and immediates
+pub fn arb_decoded_opcode<const N: usize, E: VmEncodingMode<N>>(
+) -> impl Strategy<Value = DecodedOpcode<N, E>>
+where
+    E::PcOrImm: Arbitrary,
+{
+    (
+        arb_opcode_variant(),
+        arb_condition(),
+        [
+            arb_register_index(),
+            arb_register_index(),
+            arb_register_index(),
+            arb_register_index(),
+        ],
+        any::<E::PcOrImm>(),
+        any::<E::PcOrImm>(),
+    )
+        .prop_map(|(variant, condition, registers, imm_0, imm_1)| {
+            let [src0_reg_idx, src1_reg_idx, dst0_reg_idx, dst1_reg_idx] = registers;
+            DecodedOpcode {
+                variant,
+                condition,
+                src0_reg_idx,
+                src1_reg_idx,
+                dst0_reg_idx,
+                dst1_reg_idx,
+                imm_0,
+                imm_1,
+            }
+        })
+}
+
+pub fn arb_production_opcode() -> impl Strategy<Value = DecodedOpcode<8, EncodingModeProduction>> {
+    arb_decoded_opcode::<8, EncodingModeProduction>()
+}
+
+pub fn arb_testing_opcode() -> impl Strategy<Value = DecodedOpcode<16, EncodingModeTesting>> {
+    arb_decoded_opcode::<16, EncodingModeTesting>()
+}
+
+// variant index and the unused gap after it, everything from the condition upwards is free
+const VARIANT_AND_GAP_MASK: u64 = (1u64 << CONDITIONAL_BITS_SHIFT) - 1;
+
+/// Canonical encodings that use the canonical variant index
+pub fn arb_production_encoding() -> impl Strategy<Value = u64> {
+    (any::<u64>(), select(canonical_variant_indices()))
+        .prop_map(|(raw, idx)| (raw & !VARIANT_AND_GAP_MASK) | idx as u64)
+}
+
+/// Canonical encodings that use the canonical variant index. The testing mode
+/// ignores everything above `imm_1`, so these bits are always zero
+pub fn arb_testing_encoding() -> impl Strategy<Value = u128> {
+    const USED_BITS_MASK: u128 = (1u128 << 96) - 1;
+
+    (any::<u128>(), select(canonical_variant_indices())).prop_map(|(raw, idx)| {
+        (raw & USED_BITS_MASK & !(VARIANT_AND_GAP_MASK as u128)) | idx as u128
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn production_opcodes_round_trip(opcode in arb_production_opcode()) {
+            let raw = opcode.serialize_as_integer();
+            prop_assert!(EncodingModeProduction::is_canonical_encoding(raw));
+            let (decoded, _) = EncodingModeProduction::parse_preliminary_variant_and_absolute_number(raw);
+            prop_assert_eq!(decoded, opcode);
+        }
+
+        #[test]
+        fn testing_opcodes_round_trip(opcode in arb_testing_opcode()) {
+            let raw = opcode.serialize_as_integer();
+            prop_assert!(EncodingModeTesting::is_canonical_encoding(raw));
+            let (decoded, _) = EncodingModeTesting::parse_preliminary_variant_and_absolute_number(raw);
+            prop_assert_eq!(decoded, opcode);
+        }
+
+        #[test]
+        fn production_encodings_round_trip(raw in arb_production_encoding()) {
+            prop_assert!(EncodingModeProduction::is_canonical_encoding(raw));
+            let (decoded, _) = EncodingModeProduction::parse_preliminary_variant_and_absolute_number(raw);
+            prop_assert_eq!(decoded.serialize_as_integer(), raw);
+        }
+
+        #[test]
+        fn testing_encodings_round_trip(raw in arb_testing_encoding()) {
+            prop_assert!(EncodingModeTesting::is_canonical_encoding(raw));
+            let (decoded, _) = EncodingModeTesting::parse_preliminary_variant_and_absolute_number(raw);
+            prop_assert_eq!(decoded.serialize_as_integer(), raw);
+        }
+
+        #[test]
+        fn decoding_is_idempotent(raw in any::<u64>()) {
+            // any integer decodes, and encoding the result gives an integer that decodes the same way
+            let (decoded, _) = EncodingModeProduction::parse_preliminary_variant_and_absolute_number(raw);
+            let reencoded = decoded.serialize_as_integer();
+            let (redecoded, _) = EncodingModeProduction::parse_preliminary_variant_and_absolute_number(reencoded);
+            prop_assert_eq!(redecoded, decoded);
+            prop_assert!(EncodingModeProduction::is_canonical_encoding(reencoded));
+        }
+    }
+}
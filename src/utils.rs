@@ -1,4 +1,10 @@
-use crate::decoding::{EncodingModeProduction, VmEncodingMode};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use ethereum_types::U256;
+
+use crate::decoding::{AllowedPcOrImm, EncodingModeProduction, VmEncodingMode};
+use crate::DecodedOpcode;
 
 pub const fn split_as_u4(value: u8) -> (u8, u8) {
     (value & ((1u8 << 4) - 1), value >> 4)
@@ -10,26 +16,105 @@ pub const fn merge_u4(low: u8, high: u8) -> u8 {
     low | (high << 4)
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BytecodeHashError {
+    EvenNumberOfWords {
+        num_words: usize,
+    },
+    TooLong {
+        num_words: usize,
+        max_num_words: usize,
+    },
+    /// Bytecode is given as bytes that don't form whole words
+    UnalignedLength {
+        num_bytes: u64,
+    },
+    SerializationFailed,
+}
+
+impl core::fmt::Display for BytecodeHashError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BytecodeHashError::EvenNumberOfWords { num_words } => write!(
+                f,
+                "bytecode must have an odd number of words, but has {}",
+                num_words
+            ),
+            BytecodeHashError::TooLong {
+                num_words,
+                max_num_words,
+            } => write!(
+                f,
+                "bytecode has {} words, while at most {} are allowed",
+                num_words, max_num_words
+            ),
+            BytecodeHashError::UnalignedLength { num_bytes } => write!(
+                f,
+                "bytecode length must be a multiple of 32 bytes, but is {}",
+                num_bytes
+            ),
+            BytecodeHashError::SerializationFailed => {
+                write!(f, "failed to serialize versioned hash")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BytecodeHashError {}
+
+/// Checks the number of words in the bytecode against the rules of hashing
+pub fn check_bytecode_length_for_mode<const N: usize, E: VmEncodingMode<N>>(
+    num_words: usize,
+) -> Result<(), BytecodeHashError> {
+    // bytecode should have an odd number of 32-byte words for ease of use of SHA256 round function
+    if num_words % 2 != 1 {
+        return Err(BytecodeHashError::EvenNumberOfWords { num_words });
+    }
+
+    // limit of the bytecode length can be different in different modes
+    if num_words as u64 > E::PcOrImm::max().as_u64() {
+        return Err(BytecodeHashError::TooLong {
+            num_words,
+            max_num_words: E::PcOrImm::max().as_u64() as usize,
+        });
+    }
+
+    Ok(())
+}
+
 /// Internally we use versioned hash by our convensions
-pub fn bytecode_to_code_hash(bytecode_words: &[[u8; 32]]) -> Result<[u8; 32], ()> {
+pub fn bytecode_to_code_hash(bytecode_words: &[[u8; 32]]) -> Result<[u8; 32], BytecodeHashError> {
     bytecode_to_code_hash_for_mode::<8, EncodingModeProduction>(bytecode_words)
 }
 
 /// Internally we use versioned hash by our convensions
 pub fn bytecode_to_code_hash_for_mode<const N: usize, E: VmEncodingMode<N>>(
     bytecode_words: &[[u8; 32]],
-) -> Result<[u8; 32], ()> {
-    // bytecode should have an odd number of 32-byte words for ease of use of SHA256 round function
-    if bytecode_words.len() % 2 != 1 {
-        return Err(());
-    }
+) -> Result<[u8; 32], BytecodeHashError> {
+    let hashes = bytecode_to_code_hash_and_digest_for_mode::<N, E>(bytecode_words)?;
 
-    // limit of the bytecode length can be different in different modes
-    use crate::decoding::AllowedPcOrImm;
+    Ok(hashes.versioned_hash)
+}
 
-    if bytecode_words.len() as u64 > E::PcOrImm::max().as_u64() {
-        return Err(());
-    }
+/// Versioned hash together with the full sha256 digest of the bytecode,
+/// as the versioned hash only keeps the last 28 bytes of the latter
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BytecodeHashes {
+    pub versioned_hash: [u8; 32],
+    pub digest: [u8; 32],
+}
+
+pub fn bytecode_to_code_hash_and_digest(
+    bytecode_words: &[[u8; 32]],
+) -> Result<BytecodeHashes, BytecodeHashError> {
+    bytecode_to_code_hash_and_digest_for_mode::<8, EncodingModeProduction>(bytecode_words)
+}
+
+pub fn bytecode_to_code_hash_and_digest_for_mode<const N: usize, E: VmEncodingMode<N>>(
+    bytecode_words: &[[u8; 32]],
+) -> Result<BytecodeHashes, BytecodeHashError> {
+    check_bytecode_length_for_mode::<N, E>(bytecode_words.len())?;
 
     use sha2::{Digest, Sha256};
 
@@ -55,7 +140,390 @@ pub fn bytecode_to_code_hash_for_mode<const N: usize, E: VmEncodingMode<N>>(
         );
     // this will place all the byte markers, lengths, etc
 
-    let versioned_hash_bytes = versioned_hash.serialize().ok_or(())?;
+    let versioned_hash_bytes = versioned_hash
+        .serialize()
+        .ok_or(BytecodeHashError::SerializationFailed)?;
+
+    Ok(BytecodeHashes {
+        versioned_hash: versioned_hash_bytes,
+        digest: output,
+    })
+}
+
+/// Incremental version of `bytecode_to_code_hash_for_mode` that accepts the bytecode
+/// in chunks of arbitrary length, so it doesn't have to be reshaped into words first
+#[derive(Clone, Debug)]
+pub struct BytecodeHasher<const N: usize = 8, E: VmEncodingMode<N> = EncodingModeProduction> {
+    hasher: sha2::Sha256,
+    num_bytes: u64,
+    _marker: core::marker::PhantomData<E>,
+}
+
+impl BytecodeHasher<8, EncodingModeProduction> {
+    pub fn new() -> Self {
+        Self::new_for_mode()
+    }
+}
+
+impl<const N: usize, E: VmEncodingMode<N>> Default for BytecodeHasher<N, E> {
+    fn default() -> Self {
+        Self::new_for_mode()
+    }
+}
+
+impl<const N: usize, E: VmEncodingMode<N>> BytecodeHasher<N, E> {
+    pub fn new_for_mode() -> Self {
+        use sha2::Digest;
+
+        Self {
+            hasher: sha2::Sha256::new(),
+            num_bytes: 0,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        use sha2::Digest;
+
+        self.hasher.update(chunk);
+        self.num_bytes += chunk.len() as u64;
+    }
+
+    pub fn finalize(
+        self,
+    ) -> Result<crate::VersionedHashGeneric<crate::ContractCodeSha256>, BytecodeHashError> {
+        use crate::{ContractCodeSha256, VersionedHashGeneric};
+        use sha2::Digest;
+
+        if !self.num_bytes.is_multiple_of(32) {
+            return Err(BytecodeHashError::UnalignedLength {
+                num_bytes: self.num_bytes,
+            });
+        }
+
+        let num_words = self.num_bytes / 32;
+        check_bytecode_length_for_mode::<N, E>(num_words as usize)?;
+
+        let mut output = [0u8; 32];
+        output[..].copy_from_slice(self.hasher.finalize().as_slice());
+
+        let versioned_hash =
+            VersionedHashGeneric::<ContractCodeSha256>::from_digest_and_preimage_num_words(
+                output,
+                num_words as u16,
+            );
+
+        Ok(versioned_hash)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const N: usize, E: VmEncodingMode<N>> std::io::Write for BytecodeHasher<N, E> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Lowercase hex with the `0x` prefix
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(2 + 2 * bytes.len());
+    result.push_str("0x");
+    for el in bytes.iter() {
+        result.push_str(&alloc::format!("{:02x}", el));
+    }
+
+    result
+}
+
+fn hex_digits(input: &str) -> Option<&str> {
+    let digits = input.strip_prefix("0x").unwrap_or(input);
+    // `from_str_radix` accepts a sign, so the digits are checked first
+    if !digits.bytes().all(|el| el.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some(digits)
+}
+
+/// `0x` prefix is optional
+pub fn bytes_from_hex(input: &str) -> Option<Vec<u8>> {
+    let digits = hex_digits(input)?.as_bytes();
+    let pairs = digits.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+
+    pairs
+        .map(|el| u8::from_str_radix(core::str::from_utf8(el).ok()?, 16).ok())
+        .collect()
+}
+
+/// `0x` prefix is optional, leading zeroes may be omitted
+pub fn u256_from_hex(input: &str) -> Option<U256> {
+    let digits = hex_digits(input)?;
+    if digits.is_empty() {
+        return None;
+    }
+
+    U256::from_str_radix(digits, 16).ok()
+}
+
+/// Splits the bytecode into the words that it is hashed and executed as
+pub fn bytecode_to_words(bytecode: &[u8]) -> Result<Vec<[u8; 32]>, BytecodeHashError> {
+    let words = bytecode.chunks_exact(32);
+    if !words.remainder().is_empty() {
+        return Err(BytecodeHashError::UnalignedLength {
+            num_bytes: bytecode.len() as u64,
+        });
+    }
+
+    Ok(words
+        .map(|el| el.try_into().expect("chunk is 32 bytes long"))
+        .collect())
+}
+
+/// Instruction as it is stored in the bytecode, along with its decoded form
+pub type RawAndDecodedOpcode<const N: usize, E> = (
+    <E as VmEncodingMode<N>>::IntegerRepresentation,
+    DecodedOpcode<N, E>,
+);
+
+/// Raw and decoded instructions of the bytecode in the order of execution, including the
+/// ones in the data section. Lengths are only checked for whole words, not for hashing
+pub fn decode_bytecode(
+    bytecode: &[u8],
+) -> Result<Vec<RawAndDecodedOpcode<8, EncodingModeProduction>>, BytecodeHashError> {
+    decode_bytecode_for_mode::<8, EncodingModeProduction>(bytecode)
+}
+
+pub fn decode_bytecode_for_mode<const N: usize, E: VmEncodingMode<N>>(
+    bytecode: &[u8],
+) -> Result<Vec<RawAndDecodedOpcode<N, E>>, BytecodeHashError> {
+    let instructions_per_word = 32 / N;
+
+    let words = bytecode_to_words(bytecode)?;
+    let mut result = Vec::with_capacity(words.len() * instructions_per_word);
+    for word in words.iter() {
+        let word = U256::from_big_endian(word);
+        for slot in 0..instructions_per_word {
+            let raw = E::integer_representaiton_from_u256(
+                word,
+                E::PcOrImm::from_u64_clipped(slot as u64),
+            );
+            let (decoded, _) = E::parse_preliminary_variant_and_absolute_number(raw);
+            result.push((raw, decoded));
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::decoding::EncodingModeTesting;
+
+    fn test_bytecode(num_words: usize) -> Vec<[u8; 32]> {
+        (0..num_words)
+            .map(|i| {
+                let mut word = [0u8; 32];
+                for (j, byte) in word.iter_mut().enumerate() {
+                    *byte = (i * 31 + j * 7) as u8;
+                }
+                word
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(bytes_to_hex(&[0x01, 0xab]), "0x01ab");
+        assert_eq!(bytes_from_hex("0x01ab"), Some(vec![0x01, 0xab]));
+        assert_eq!(bytes_from_hex("01AB"), Some(vec![0x01, 0xab]));
+        assert_eq!(bytes_from_hex(""), Some(vec![]));
+        for invalid in ["0x1", "0x+1", "0x-1", "+1+1", "0x 1", "0xzz", "0x0x"] {
+            assert_eq!(bytes_from_hex(invalid), None, "{}", invalid);
+        }
+
+        assert_eq!(u256_from_hex("0x1ff"), Some(U256::from(0x1ff)));
+        assert_eq!(u256_from_hex(&"f".repeat(64)), Some(U256::MAX));
+        for invalid in ["", "0x", "+1", "0x-1", "0x 1", "1g"] {
+            assert_eq!(u256_from_hex(invalid), None, "{}", invalid);
+        }
+        assert_eq!(u256_from_hex(&"1".repeat(65)), None);
+    }
+
+    #[test]
+    fn bytecode_decoding() {
+        use crate::{AddOpcode, Opcode, RetOpcode};
 
-    Ok(versioned_hash_bytes)
+        let add = crate::OPCODES_TABLE
+            .iter()
+            .find(|el| el.opcode == Opcode::Add(AddOpcode::Add))
+            .unwrap();
+        let ret = crate::OPCODES_TABLE
+            .iter()
+            .find(|el| el.opcode == Opcode::Ret(RetOpcode::Ok))
+            .unwrap();
+        let instructions: Vec<u64> = (0..8)
+            .map(|idx| {
+                let opcode: DecodedOpcode = DecodedOpcode {
+                    variant: if idx == 7 { *ret } else { *add },
+                    imm_0: idx,
+                    ..DecodedOpcode::default()
+                };
+                opcode.serialize_as_integer()
+            })
+            .collect();
+        let bytecode: Vec<u8> = instructions
+            .iter()
+            .flat_map(|el| el.to_be_bytes())
+            .collect();
+
+        let decoded = decode_bytecode(&bytecode).unwrap();
+        assert_eq!(decoded.len(), 8);
+        for (idx, (raw, opcode)) in decoded.iter().enumerate() {
+            assert_eq!(*raw, instructions[idx]);
+            assert_eq!(opcode.imm_0, idx as u16);
+        }
+        assert_eq!(decoded[7].1.variant, *ret);
+
+        // the testing mode stores two instructions per word
+        let decoded = decode_bytecode_for_mode::<16, EncodingModeTesting>(&bytecode).unwrap();
+        assert_eq!(decoded.len(), 4);
+
+        assert_eq!(bytecode_to_words(&bytecode).unwrap().len(), 2);
+        assert!(decode_bytecode(&[]).unwrap().is_empty());
+        assert_eq!(
+            decode_bytecode(&bytecode[..40]),
+            Err(BytecodeHashError::UnalignedLength { num_bytes: 40 })
+        );
+    }
+
+    #[test]
+    fn versioned_hash_keeps_digest_and_length() {
+        use sha2::{Digest, Sha256};
+
+        for num_words in [1, 3, 33, u16::MAX as usize] {
+            let bytecode = test_bytecode(num_words);
+            let flat: Vec<u8> = bytecode.iter().flatten().copied().collect();
+            let expected_digest: [u8; 32] = Sha256::digest(&flat).into();
+
+            let hashes = bytecode_to_code_hash_and_digest(&bytecode).unwrap();
+            assert_eq!(hashes.digest, expected_digest);
+            assert_eq!(
+                bytecode_to_code_hash(&bytecode).unwrap(),
+                hashes.versioned_hash
+            );
+
+            let versioned_hash = hashes.versioned_hash;
+            assert_eq!(versioned_hash[0], 1);
+            assert_eq!(versioned_hash[1], 0);
+            assert_eq!(
+                u16::from_be_bytes([versioned_hash[2], versioned_hash[3]]) as usize,
+                num_words
+            );
+            assert_eq!(versioned_hash[4..], expected_digest[4..]);
+
+            let hashes =
+                bytecode_to_code_hash_and_digest_for_mode::<16, EncodingModeTesting>(&bytecode)
+                    .unwrap();
+            assert_eq!(hashes.digest, expected_digest);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_lengths() {
+        for num_words in [0, 2, 4] {
+            assert_eq!(
+                bytecode_to_code_hash_and_digest(&test_bytecode(num_words)),
+                Err(BytecodeHashError::EvenNumberOfWords { num_words })
+            );
+        }
+
+        let num_words = u16::MAX as usize + 2;
+        let expected = BytecodeHashError::TooLong {
+            num_words,
+            max_num_words: u16::MAX as usize,
+        };
+        assert_eq!(
+            bytecode_to_code_hash(&test_bytecode(num_words)),
+            Err(expected)
+        );
+        assert_eq!(
+            check_bytecode_length_for_mode::<8, EncodingModeProduction>(num_words),
+            Err(expected)
+        );
+        // testing mode allows for longer code, but the length must still be odd
+        assert_eq!(
+            check_bytecode_length_for_mode::<16, EncodingModeTesting>(num_words),
+            Ok(())
+        );
+        assert_eq!(
+            check_bytecode_length_for_mode::<16, EncodingModeTesting>(num_words + 1),
+            Err(BytecodeHashError::EvenNumberOfWords {
+                num_words: num_words + 1
+            })
+        );
+    }
+
+    #[test]
+    fn streaming_hasher_matches_word_hasher() {
+        for num_words in [1, 3, 5, 17, 33] {
+            let bytecode = test_bytecode(num_words);
+            let expected = bytecode_to_code_hash(&bytecode).unwrap();
+            let flat: Vec<u8> = bytecode.iter().flatten().copied().collect();
+
+            for chunk_size in [1, 7, 32, 33, 100, flat.len()] {
+                let mut hasher = BytecodeHasher::new();
+                for chunk in flat.chunks(chunk_size) {
+                    hasher.update(chunk);
+                }
+                let hash = hasher.finalize().unwrap().serialize().unwrap();
+                assert_eq!(hash, expected);
+            }
+
+            #[cfg(feature = "std")]
+            {
+                let mut hasher = BytecodeHasher::new();
+                std::io::copy(&mut &flat[..], &mut hasher).unwrap();
+                assert_eq!(hasher.finalize().unwrap().serialize().unwrap(), expected);
+            }
+
+            let expected =
+                bytecode_to_code_hash_for_mode::<16, EncodingModeTesting>(&bytecode).unwrap();
+            let mut hasher = BytecodeHasher::<16, EncodingModeTesting>::new_for_mode();
+            hasher.update(&flat);
+            assert_eq!(hasher.finalize().unwrap().serialize().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn streaming_hasher_rejects_invalid_lengths() {
+        let flat: Vec<u8> = test_bytecode(2).iter().flatten().copied().collect();
+
+        let mut hasher = BytecodeHasher::new();
+        hasher.update(&flat);
+        assert_eq!(
+            hasher.finalize(),
+            Err(BytecodeHashError::EvenNumberOfWords { num_words: 2 })
+        );
+        assert_eq!(
+            bytecode_to_code_hash(&test_bytecode(2)),
+            Err(BytecodeHashError::EvenNumberOfWords { num_words: 2 })
+        );
+
+        let mut hasher = BytecodeHasher::new();
+        hasher.update(&flat[..33]);
+        assert_eq!(
+            hasher.finalize(),
+            Err(BytecodeHashError::UnalignedLength { num_bytes: 33 })
+        );
+    }
 }
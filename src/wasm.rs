@@ -0,0 +1,287 @@
+// Bindings for block explorers and browser based debuggers. Everything is exchanged as
+// `0x` prefixed hex strings or plain objects in the `serde` representation of the types.
+// Tests of the bindings themselves are run with `wasm-pack test --headless --firefox -- --features wasm --lib`
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use ethereum_types::U256;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::decoding::{EncodingModeProduction, VmEncodingMode};
+use crate::{
+    bytes_from_hex, bytes_to_hex, u256_from_hex, BytecodeHasher, DecodedOpcode, FarCallABI,
+    FarCallForwardPageType, FatPointer, RetABI, RetForwardPageType,
+};
+
+const INSTRUCTION_BYTES: usize = 8;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Instruction {
+    /// Position in the code, in instructions
+    pub pc: usize,
+    pub raw: String,
+    /// Non-canonical encodings are decoded, but the VM would not execute them
+    pub canonical: bool,
+    pub ergs_price: u32,
+    #[serde(flatten)]
+    pub decoded: DecodedOpcode,
+}
+
+fn decode_raw(pc: usize, raw: u64) -> Instruction {
+    // JS numbers can not hold all 64 bits, so the raw value is kept as a hex string
+    let (decoded, _) = EncodingModeProduction::parse_preliminary_variant_and_absolute_number(raw);
+
+    Instruction {
+        pc,
+        raw: bytes_to_hex(&raw.to_be_bytes()),
+        canonical: EncodingModeProduction::is_canonical_encoding(raw),
+        ergs_price: decoded.variant.ergs_price(),
+        decoded,
+    }
+}
+
+pub fn decode_instruction_bytes(bytes: &[u8]) -> Result<Instruction, String> {
+    let bytes: [u8; INSTRUCTION_BYTES] = bytes.try_into().map_err(|_| {
+        alloc::format!(
+            "instruction must be {} bytes long, got {}",
+            INSTRUCTION_BYTES,
+            bytes.len()
+        )
+    })?;
+
+    Ok(decode_raw(0, u64::from_be_bytes(bytes)))
+}
+
+pub fn decode_bytecode_bytes(bytecode: &[u8]) -> Result<Vec<Instruction>, String> {
+    let instructions = crate::decode_bytecode(bytecode).map_err(|err| err.to_string())?;
+
+    Ok(instructions
+        .into_iter()
+        .enumerate()
+        .map(|(pc, (raw, _))| decode_raw(pc, raw))
+        .collect())
+}
+
+/// `0x` prefix is optional
+pub fn parse_u256(input: &str) -> Result<U256, String> {
+    u256_from_hex(input).ok_or_else(|| alloc::format!("`{}` is not a 256 bit hex value", input))
+}
+
+pub fn bytecode_hash_bytes(bytecode: &[u8]) -> Result<[u8; 32], String> {
+    let mut hasher = BytecodeHasher::new();
+    hasher.update(bytecode);
+    let hash = hasher.finalize().map_err(|err| err.to_string())?;
+
+    hash.serialize()
+        .ok_or_else(|| "failed to serialize versioned hash".to_string())
+}
+
+// ABI types are serialized as words, while explorers want to show the fields
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct FatPointerFields {
+    pub offset: u32,
+    pub memory_page: u32,
+    pub start: u32,
+    pub length: u32,
+}
+
+impl From<FatPointer> for FatPointerFields {
+    fn from(pointer: FatPointer) -> Self {
+        Self {
+            offset: pointer.offset,
+            memory_page: pointer.memory_page,
+            start: pointer.start,
+            length: pointer.length,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct FarCallAbiFields {
+    pub memory_quasi_fat_pointer: FatPointerFields,
+    pub ergs_passed: u32,
+    pub shard_id: u8,
+    pub forwarding_mode: FarCallForwardPageType,
+    pub constructor_call: bool,
+    pub to_system: bool,
+}
+
+impl From<FarCallABI> for FarCallAbiFields {
+    fn from(abi: FarCallABI) -> Self {
+        Self {
+            memory_quasi_fat_pointer: abi.memory_quasi_fat_pointer.into(),
+            ergs_passed: abi.ergs_passed,
+            shard_id: abi.shard_id,
+            forwarding_mode: abi.forwarding_mode,
+            constructor_call: abi.constructor_call,
+            to_system: abi.to_system,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct RetAbiFields {
+    pub memory_quasi_fat_pointer: FatPointerFields,
+    pub page_forwarding_mode: RetForwardPageType,
+}
+
+impl From<RetABI> for RetAbiFields {
+    fn from(abi: RetABI) -> Self {
+        Self {
+            memory_quasi_fat_pointer: abi.memory_quasi_fat_pointer.into(),
+            page_forwarding_mode: abi.page_forwarding_mode,
+        }
+    }
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn bytes_from_js(input: &str) -> Result<Vec<u8>, JsError> {
+    bytes_from_hex(input)
+        .ok_or_else(|| JsError::new(&alloc::format!("invalid hex string `{}`", input)))
+}
+
+fn u256_from_js(input: &str) -> Result<U256, JsError> {
+    parse_u256(input).map_err(|err| JsError::new(&err))
+}
+
+/// Decodes a single 8 byte instruction
+#[wasm_bindgen(js_name = decodeInstruction)]
+pub fn decode_instruction(hex: &str) -> Result<JsValue, JsError> {
+    let instruction =
+        decode_instruction_bytes(&bytes_from_js(hex)?).map_err(|err| JsError::new(&err))?;
+
+    to_js(&instruction)
+}
+
+/// Decodes every instruction of the bytecode, including the ones in the data section
+#[wasm_bindgen(js_name = decodeBytecode)]
+pub fn decode_bytecode(bytecode: &[u8]) -> Result<JsValue, JsError> {
+    let instructions = decode_bytecode_bytes(bytecode).map_err(|err| JsError::new(&err))?;
+
+    to_js(&instructions)
+}
+
+#[wasm_bindgen(js_name = parseFarCallAbi)]
+pub fn parse_far_call_abi(hex: &str) -> Result<JsValue, JsError> {
+    to_js(&FarCallAbiFields::from(FarCallABI::from_u256(
+        u256_from_js(hex)?,
+    )))
+}
+
+#[wasm_bindgen(js_name = parseRetAbi)]
+pub fn parse_ret_abi(hex: &str) -> Result<JsValue, JsError> {
+    to_js(&RetAbiFields::from(RetABI::from_u256(u256_from_js(hex)?)))
+}
+
+#[wasm_bindgen(js_name = parseFatPointer)]
+pub fn parse_fat_pointer(hex: &str) -> Result<JsValue, JsError> {
+    to_js(&FatPointerFields::from(FatPointer::from_u256(
+        u256_from_js(hex)?,
+    )))
+}
+
+/// Versioned hash of the bytecode as a hex string
+#[wasm_bindgen(js_name = bytecodeHash)]
+pub fn bytecode_hash(bytecode: &[u8]) -> Result<String, JsError> {
+    let hash = bytecode_hash_bytes(bytecode).map_err(|err| JsError::new(&err))?;
+
+    Ok(bytes_to_hex(&hash))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bytecode_to_code_hash, Opcode, RetOpcode};
+
+    fn test_bytecode() -> Vec<u8> {
+        let nop = EncodingModeProduction::nop_encoding().to_be_bytes();
+        let panic = EncodingModeProduction::exception_revert_encoding().to_be_bytes();
+        let mut word = Vec::new();
+        for el in [nop, nop, nop, panic].iter() {
+            word.extend_from_slice(el);
+        }
+
+        word
+    }
+
+    #[test]
+    fn decode() {
+        let panic = EncodingModeProduction::exception_revert_encoding();
+        let instruction = decode_instruction_bytes(&panic.to_be_bytes()).unwrap();
+        assert_eq!(instruction.raw, bytes_to_hex(&panic.to_be_bytes()));
+        assert!(instruction.canonical);
+        assert_eq!(
+            instruction.decoded.variant.opcode,
+            Opcode::Ret(RetOpcode::Panic)
+        );
+        assert!(decode_instruction_bytes(&[0u8; 7]).is_err());
+
+        let instructions = decode_bytecode_bytes(&test_bytecode()).unwrap();
+        assert_eq!(instructions.len(), 4);
+        for (pc, el) in instructions.iter().enumerate() {
+            assert_eq!(el.pc, pc);
+        }
+        assert_eq!(instructions[3].raw, bytes_to_hex(&panic.to_be_bytes()));
+        assert!(decode_bytecode_bytes(&[0u8; 31]).is_err());
+    }
+
+    #[test]
+    fn hash_and_abi() {
+        let bytecode = test_bytecode();
+        let mut word = [0u8; 32];
+        word.copy_from_slice(&bytecode);
+        assert_eq!(
+            bytecode_hash_bytes(&bytecode).unwrap(),
+            bytecode_to_code_hash(&[word]).unwrap()
+        );
+        assert!(bytecode_hash_bytes(&bytecode[..16]).is_err());
+
+        let pointer = FatPointer {
+            offset: 1,
+            memory_page: 2,
+            start: 3,
+            length: 4,
+        };
+        let hex = alloc::format!("{:#x}", pointer.to_u256());
+        assert_eq!(FatPointer::from_u256(parse_u256(&hex).unwrap()), pointer);
+        assert!(parse_u256("0xzz").is_err());
+        assert!(parse_u256(&alloc::format!("0x{}", "00".repeat(33))).is_err());
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    mod wasm {
+        use super::*;
+        use wasm_bindgen_test::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        fn bindings() {
+            let panic = EncodingModeProduction::exception_revert_encoding();
+            let decoded = decode_instruction(&bytes_to_hex(&panic.to_be_bytes())).unwrap();
+            let opcode = js_sys::Reflect::get(&decoded, &"variant".into()).unwrap();
+            let opcode = js_sys::Reflect::get(&opcode, &"opcode".into()).unwrap();
+            assert_eq!(opcode.as_string().unwrap(), "Ret.Panic");
+
+            let decoded = decode_bytecode(&test_bytecode()).unwrap();
+            assert_eq!(js_sys::Array::from(&decoded).length(), 4);
+
+            let hash = bytecode_hash(&test_bytecode()).unwrap();
+            assert_eq!(hash.len(), 66);
+
+            let abi = parse_far_call_abi("0x01").unwrap();
+            let pointer = js_sys::Reflect::get(&abi, &"memory_quasi_fat_pointer".into()).unwrap();
+            let offset = js_sys::Reflect::get(&pointer, &"offset".into()).unwrap();
+            assert_eq!(offset.as_f64(), Some(1.0));
+            assert!(parse_ret_abi("0x01").is_ok());
+            assert!(parse_fat_pointer("not hex").is_err());
+        }
+    }
+}
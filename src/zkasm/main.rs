@@ -0,0 +1,493 @@
+use std::io::Write;
+use std::path::Path;
+
+use zkevm_opcode_defs::decoding::{EncodingModeProduction, VmEncodingMode};
+use zkevm_opcode_defs::ethereum_types::U256;
+use zkevm_opcode_defs::{
+    bytecode_to_code_hash, bytecode_to_words, bytes_from_hex, bytes_to_hex, decode_bytecode,
+    synthesize_opcode_decoding_tables, u256_from_hex, DecodedOpcode, FarCallABI, ISAVersion,
+    Opcode, OpcodeVariant, RetABI, ALL_CONDITIONS, ALL_ISA_VERSIONS, DEFAULT_ISA_VERSION,
+    OPCODES_TABLE, OPCODES_TABLE_WIDTH,
+};
+
+const USAGE: &str = "Usage: zkasm <command> [<args>]
+
+    decode <hex or file>     disassemble a single 8 byte instruction, or every instruction of
+                             the bytecode given as hex or as a file with raw or hex encoded bytes
+    encode <mnemonic> [<field>=<value> ...]
+                             encode an instruction of the default ISA version. `src0`, `dst0` and
+                             `flags=<0|1>,<0|1>` pick the variant as printed by `table`, the first
+                             one is taken for the omitted ones. `cond` is the condition,
+                             `regs=<src0>,<src1>,<dst0>,<dst1>` and `imm=<imm_0>,<imm_1>` the rest
+    hash <file>              print the versioned code hash of the bytecode in the file
+    abi far-call <hex>       print the fields of the far call ABI word
+    abi ret <hex>            print the fields of the ret ABI word
+    table [--version <N>]    dump the decoding table, of the default ISA version if no version is given
+    price <mnemonic>         print the ergs price of the opcode split by components
+
+Mnemonics are like `Log.StorageWrite`, the sub-variant may be omitted when the opcode has
+only one, so `Add` is the same as `Add.Add`";
+
+const INSTRUCTION_BYTES: usize = 8;
+
+enum Error {
+    Input(String),
+    Output(std::io::Error),
+}
+
+impl From<String> for Error {
+    fn from(err: String) -> Self {
+        Error::Input(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Output(err)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Help,
+    Decode(String),
+    Encode(DecodedOpcode),
+    Hash(String),
+    FarCallAbi(U256),
+    RetAbi(U256),
+    Table(ISAVersion),
+    Price(Opcode),
+}
+
+/// `0x` prefix is optional, whitespace is ignored
+fn parse_hex(input: &str) -> Result<Vec<u8>, String> {
+    let digits: String = input.split_whitespace().collect();
+
+    bytes_from_hex(&digits).ok_or_else(|| "invalid hex string".to_owned())
+}
+
+fn parse_u256(input: &str) -> Result<U256, String> {
+    u256_from_hex(input).ok_or_else(|| format!("`{}` is not a 256 bit hex value", input))
+}
+
+/// Decimal, or hex with the `0x` prefix
+fn parse_number<T: TryFrom<u64>>(input: &str) -> Result<T, String> {
+    let (digits, radix) = match input.strip_prefix("0x") {
+        Some(digits) => (digits, 16),
+        None => (input, 10),
+    };
+    // `from_str_radix` accepts a sign, so the digits are checked first
+    if digits.is_empty() || !digits.chars().all(|el| el.is_digit(radix)) {
+        return Err(format!("`{}` is not a number", input));
+    }
+
+    u64::from_str_radix(digits, radix)
+        .ok()
+        .and_then(|el| T::try_from(el).ok())
+        .ok_or_else(|| format!("`{}` is out of range", input))
+}
+
+fn parse_list<const M: usize, T>(
+    input: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<[T; M], String> {
+    let values = input.split(',').map(parse).collect::<Result<Vec<_>, _>>()?;
+
+    values
+        .try_into()
+        .map_err(|_| format!("expected {} comma separated values, got `{}`", M, input))
+}
+
+fn parse_flag(input: &str) -> Result<bool, String> {
+    match input {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(format!("`{}` is not a flag, expected 0 or 1", input)),
+    }
+}
+
+fn parse_version(arg: &str) -> Result<ISAVersion, String> {
+    let version = arg
+        .parse::<u8>()
+        .map(ISAVersion)
+        .map_err(|_| format!("`{}` is not a valid version number", arg))?;
+    if !ALL_ISA_VERSIONS.contains(&version) {
+        return Err(format!("unknown ISA version {}", version.0));
+    }
+
+    Ok(version)
+}
+
+fn parse_opcode(mnemonic: &str) -> Result<Opcode, String> {
+    if let Some(opcode) = Opcode::from_mnemonic(mnemonic) {
+        return Ok(opcode);
+    }
+
+    let prefix = format!("{}.", mnemonic);
+    let candidates: Vec<String> = Opcode::all_variants()
+        .iter()
+        .map(|el| el.mnemonic())
+        .filter(|el| el.starts_with(&prefix))
+        .collect();
+    match candidates.as_slice() {
+        [] => Err(format!("unknown opcode `{}`", mnemonic)),
+        [single] => Ok(Opcode::from_mnemonic(single).expect("mnemonic is taken from the opcode")),
+        _ => Err(format!(
+            "`{}` has several sub-variants: {}",
+            mnemonic,
+            candidates.join(", ")
+        )),
+    }
+}
+
+fn parse_encode(mnemonic: &str, fields: &[&str]) -> Result<DecodedOpcode, String> {
+    let opcode = parse_opcode(mnemonic)?;
+
+    let mut result = DecodedOpcode::default();
+    let (mut src0, mut dst0, mut flags) = (None, None, None);
+    for field in fields {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("expected `<field>=<value>`, got `{}`", field))?;
+        match key {
+            "src0" => src0 = Some(value),
+            "dst0" => dst0 = Some(value),
+            "flags" => flags = Some(parse_list::<2, _>(value, parse_flag)?),
+            "cond" => {
+                result.condition = ALL_CONDITIONS
+                    .iter()
+                    .copied()
+                    .find(|el| format!("{:?}", el) == value)
+                    .ok_or_else(|| format!("unknown condition `{}`", value))?
+            }
+            "regs" => {
+                let [src0_reg_idx, src1_reg_idx, dst0_reg_idx, dst1_reg_idx] =
+                    parse_list::<4, _>(value, parse_number)?;
+                result.src0_reg_idx = src0_reg_idx;
+                result.src1_reg_idx = src1_reg_idx;
+                result.dst0_reg_idx = dst0_reg_idx;
+                result.dst1_reg_idx = dst1_reg_idx;
+            }
+            "imm" => [result.imm_0, result.imm_1] = parse_list::<2, _>(value, parse_number)?,
+            _ => return Err(format!("unknown field `{}`", key)),
+        }
+    }
+
+    result.variant = OPCODES_TABLE
+        .iter()
+        .copied()
+        .find(|el| {
+            el.opcode == opcode
+                && src0.is_none_or(|src0| format!("{:?}", el.src0_operand_type) == src0)
+                && dst0.is_none_or(|dst0| format!("{:?}", el.dst0_operand_type) == dst0)
+                && flags.is_none_or(|flags| el.flags == flags)
+        })
+        .ok_or_else(|| {
+            format!(
+                "no variant of `{}` in the default ISA version matches the fields",
+                opcode.mnemonic()
+            )
+        })?;
+
+    Ok(result)
+}
+
+fn parse_args(args: &[&str]) -> Result<Command, String> {
+    if args.iter().any(|el| *el == "--help" || *el == "-h") {
+        return Ok(Command::Help);
+    }
+
+    match args {
+        ["decode", input] => Ok(Command::Decode(input.to_string())),
+        ["encode", mnemonic, fields @ ..] => Ok(Command::Encode(parse_encode(mnemonic, fields)?)),
+        ["hash", path] => Ok(Command::Hash(path.to_string())),
+        ["abi", "far-call", word] => Ok(Command::FarCallAbi(parse_u256(word)?)),
+        ["abi", "ret", word] => Ok(Command::RetAbi(parse_u256(word)?)),
+        ["abi", ..] => Err("expected `abi far-call <hex>` or `abi ret <hex>`".to_owned()),
+        ["table"] => Ok(Command::Table(DEFAULT_ISA_VERSION)),
+        ["table", "--version", version] => Ok(Command::Table(parse_version(version)?)),
+        ["price", mnemonic] => Ok(Command::Price(parse_opcode(mnemonic)?)),
+        [] => Err("expected a command".to_owned()),
+        [command, ..] => Err(format!("invalid arguments for `{}`", command)),
+    }
+}
+
+/// Files may contain either raw bytes or hex, as compilers output both
+fn read_bytecode(path: &Path) -> Result<Vec<u8>, String> {
+    let content =
+        std::fs::read(path).map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+
+    match std::str::from_utf8(&content).ok().map(parse_hex) {
+        Some(Ok(bytes)) => Ok(bytes),
+        _ => Ok(content),
+    }
+}
+
+fn format_variant(variant: &OpcodeVariant) -> String {
+    format!(
+        "{} src0: {:?}, dst0: {:?}, flags: {}|{}",
+        variant.opcode.mnemonic(),
+        variant.src0_operand_type,
+        variant.dst0_operand_type,
+        variant.flags[0] as u8,
+        variant.flags[1] as u8,
+    )
+}
+
+fn format_instruction(raw: u64, decoded: &DecodedOpcode) -> String {
+    let canonical = if EncodingModeProduction::is_canonical_encoding(raw) {
+        ""
+    } else {
+        " (non-canonical)"
+    };
+
+    format!(
+        "{:#018x} {}, condition: {:?}, regs: r{}, r{} -> r{}, r{}, imm: {:#x}, {:#x}{}",
+        raw,
+        format_variant(&decoded.variant),
+        decoded.condition,
+        decoded.src0_reg_idx,
+        decoded.src1_reg_idx,
+        decoded.dst0_reg_idx,
+        decoded.dst1_reg_idx,
+        decoded.imm_0,
+        decoded.imm_1,
+        canonical,
+    )
+}
+
+fn decode(out: &mut impl Write, input: &str) -> Result<(), Error> {
+    let path = Path::new(input);
+    let bytes = if path.is_file() {
+        read_bytecode(path)?
+    } else {
+        parse_hex(input)?
+    };
+
+    if bytes.len() == INSTRUCTION_BYTES {
+        let raw = u64::from_be_bytes(bytes.try_into().expect("length is checked"));
+        let (decoded, _): (DecodedOpcode, _) =
+            EncodingModeProduction::parse_preliminary_variant_and_absolute_number(raw);
+        writeln!(out, "{}", decoded)?;
+        writeln!(out, "Ergs price: {}", decoded.variant.price_breakdown())?;
+        writeln!(
+            out,
+            "Canonical encoding: {}",
+            EncodingModeProduction::is_canonical_encoding(raw)
+        )?;
+
+        return Ok(());
+    }
+
+    let instructions = decode_bytecode(&bytes).map_err(|err| err.to_string())?;
+    for (pc, (raw, decoded)) in instructions.iter().enumerate() {
+        writeln!(out, "{:>6}: {}", pc, format_instruction(*raw, decoded))?;
+    }
+
+    Ok(())
+}
+
+fn encode(out: &mut impl Write, opcode: &DecodedOpcode) -> Result<(), Error> {
+    let raw = opcode
+        .try_serialize_as_integer()
+        .map_err(|err| err.to_string())?;
+    writeln!(out, "{}", format_instruction(raw, opcode))?;
+
+    Ok(())
+}
+
+fn hash(out: &mut impl Write, path: &str) -> Result<(), Error> {
+    let bytecode = read_bytecode(Path::new(path))?;
+    let hash = bytecode_to_words(&bytecode)
+        .and_then(|words| bytecode_to_code_hash(&words))
+        .map_err(|err| err.to_string())?;
+    writeln!(out, "{}", bytes_to_hex(&hash))?;
+
+    Ok(())
+}
+
+fn table(out: &mut impl Write, version: ISAVersion) -> Result<(), Error> {
+    let table = synthesize_opcode_decoding_tables(OPCODES_TABLE_WIDTH, version);
+    for (idx, variant) in table.iter().enumerate() {
+        writeln!(out, "{:>4}: {}", idx, format_variant(variant))?;
+    }
+
+    Ok(())
+}
+
+fn run(out: &mut impl Write, command: Command) -> Result<(), Error> {
+    match command {
+        Command::Help => writeln!(out, "{}", USAGE)?,
+        Command::Decode(input) => decode(out, &input)?,
+        Command::Encode(opcode) => encode(out, &opcode)?,
+        Command::Hash(path) => hash(out, &path)?,
+        Command::FarCallAbi(word) => writeln!(out, "{:#?}", FarCallABI::from_u256(word))?,
+        Command::RetAbi(word) => writeln!(out, "{:#?}", RetABI::from_u256(word))?,
+        Command::Table(version) => table(out, version)?,
+        Command::Price(opcode) => {
+            writeln!(out, "{}: {}", opcode.mnemonic(), opcode.price_breakdown())?
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|el| el.as_str()).collect();
+    let command = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{}\n\n{}", err, USAGE);
+        std::process::exit(2);
+    });
+
+    let mut out = std::io::stdout().lock();
+    match run(&mut out, command).and_then(|_| out.flush().map_err(Error::Output)) {
+        Ok(()) => {}
+        // output is piped into something like `head` that stopped reading
+        Err(Error::Output(err)) if err.kind() == std::io::ErrorKind::BrokenPipe => {}
+        Err(Error::Output(err)) => {
+            eprintln!("failed to write the output: {}", err);
+            std::process::exit(1);
+        }
+        Err(Error::Input(err)) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use zkevm_opcode_defs::{AddOpcode, Condition, LogOpcode, NopOpcode, RetOpcode};
+
+    fn output(command: Command) -> String {
+        let mut out = Vec::new();
+        match run(&mut out, command) {
+            Ok(()) => String::from_utf8(out).unwrap(),
+            Err(Error::Input(err)) => panic!("{}", err),
+            Err(Error::Output(err)) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn args() {
+        assert_eq!(
+            parse_args(&["decode", "0x00"]),
+            Ok(Command::Decode("0x00".to_owned()))
+        );
+        assert_eq!(parse_args(&["table", "-h"]), Ok(Command::Help));
+        assert_eq!(
+            parse_args(&["table"]),
+            Ok(Command::Table(DEFAULT_ISA_VERSION))
+        );
+        assert_eq!(
+            parse_args(&["abi", "ret", "0x1ff"]),
+            Ok(Command::RetAbi(U256::from(0x1ff)))
+        );
+        for invalid in [
+            &[][..],
+            &["abi", "ret", "+1"],
+            &["abi", "far-call"],
+            &["table", "--version", "255"],
+            &["hash"],
+            &["price", "Unknown"],
+        ] {
+            assert!(parse_args(invalid).is_err(), "{:?}", invalid);
+        }
+
+        assert_eq!(parse_hex("0x00 01\nff\n"), Ok(vec![0x00, 0x01, 0xff]));
+        assert!(parse_hex("+1+1").is_err());
+        assert_eq!(parse_number::<u16>("0x1f"), Ok(0x1f));
+        for invalid in ["+1", "-1", "0x", "256", "0x100"] {
+            assert!(parse_number::<u8>(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn mnemonics() {
+        let add = Opcode::Add(AddOpcode::Add);
+        assert_eq!(parse_args(&["price", "Add"]), Ok(Command::Price(add)));
+        assert_eq!(parse_args(&["price", "Add.Add"]), Ok(Command::Price(add)));
+        assert_eq!(parse_opcode("Nop"), Ok(Opcode::Nop(NopOpcode)));
+        assert_eq!(
+            parse_opcode("Log.StorageWrite"),
+            Ok(Opcode::Log(LogOpcode::StorageWrite))
+        );
+        let err = parse_opcode("Ret").unwrap_err();
+        assert!(err.contains("Ret.Ok, Ret.Revert, Ret.Panic"), "{}", err);
+
+        assert!(output(Command::Price(add)).starts_with("Add.Add: "));
+    }
+
+    #[test]
+    fn encode_and_decode() {
+        let Ok(Command::Encode(opcode)) = parse_args(&[
+            "encode",
+            "Ret.Panic",
+            "cond=Eq",
+            "regs=1,2,0,0",
+            "imm=0x10,5",
+        ]) else {
+            panic!("failed to parse the fields");
+        };
+        assert_eq!(opcode.variant.opcode, Opcode::Ret(RetOpcode::Panic));
+        assert_eq!(opcode.condition, Condition::Eq);
+        assert_eq!((opcode.src0_reg_idx, opcode.src1_reg_idx), (1, 2));
+        assert_eq!((opcode.imm_0, opcode.imm_1), (0x10, 5));
+
+        let raw = opcode.serialize_as_integer();
+        let encoded = output(Command::Encode(opcode));
+        assert!(
+            encoded.starts_with(&format!("{:#018x} Ret.Panic", raw)),
+            "{}",
+            encoded
+        );
+
+        // a single instruction is shown in full
+        let hex = bytes_to_hex(&raw.to_be_bytes());
+        let decoded = output(Command::Decode(hex));
+        assert!(decoded.contains("Condition: Eq"), "{}", decoded);
+        assert!(
+            decoded.ends_with("Canonical encoding: true\n"),
+            "{}",
+            decoded
+        );
+
+        // bytecode is listed instruction by instruction
+        let bytecode: Vec<u8> = (0..4).flat_map(|_| raw.to_be_bytes()).collect();
+        let decoded = output(Command::Decode(bytes_to_hex(&bytecode)));
+        let lines: Vec<_> = decoded.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[3], format!("     3: {}", encoded.trim_end()));
+
+        for invalid in [
+            &["encode", "Add", "src0=Unknown"][..],
+            &["encode", "Add", "flags=1"],
+            &["encode", "Add", "cond=Sometimes"],
+            &["encode", "Add", "regs"],
+            &["encode", "Add", "imm=1,2,3"],
+            &["encode", "Add", "other=1"],
+        ] {
+            assert!(parse_args(invalid).is_err(), "{:?}", invalid);
+        }
+        // registers are checked when encoding
+        let Ok(Command::Encode(opcode)) = parse_args(&["encode", "Add", "regs=16,0,0,0"]) else {
+            panic!("failed to parse the fields");
+        };
+        assert!(matches!(
+            run(&mut Vec::new(), Command::Encode(opcode)),
+            Err(Error::Input(_))
+        ));
+    }
+
+    #[test]
+    fn decoding_table() {
+        let table = output(Command::Table(DEFAULT_ISA_VERSION));
+        assert_eq!(table.lines().count(), 1 << OPCODES_TABLE_WIDTH);
+        assert_eq!(
+            table.lines().next().unwrap(),
+            format!("   0: {}", format_variant(&OPCODES_TABLE[0]))
+        );
+    }
+}